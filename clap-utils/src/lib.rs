@@ -1,11 +1,19 @@
+use bip39::Language;
 use chrono::DateTime;
-use solana_clock::{Slot, UnixTimestamp};
+use clap::Arg;
+use solana_clock::{Epoch, Slot, UnixTimestamp};
+use solana_epoch_schedule::EpochSchedule;
+use solana_hash::{HASH_BYTES, Hash, ParseHashError};
 use solana_keypair::{Keypair, read_keypair_file};
 use solana_pubkey::Pubkey;
 use solana_signer::Signer;
+use std::env;
 use std::fmt::Display;
+use std::net::{IpAddr, SocketAddr, ToSocketAddrs};
 use std::str::FromStr;
 use std::sync::Arc;
+use std::time::Duration;
+use zeroize::Zeroizing;
 
 pub fn parse_keypair_from_path(path: &str) -> Result<Arc<Keypair>, String> {
     read_keypair_file(path)
@@ -13,14 +21,60 @@ pub fn parse_keypair_from_path(path: &str) -> Result<Arc<Keypair>, String> {
         .map_err(|e| format!("failed to read keypair file '{path}': {e}"))
 }
 
+/// Reads a keypair from the environment variable `env_var`, whose value is either a base58
+/// encoded keypair or the path of a keypair file. Errors never echo the value.
+pub fn parse_keypair_from_env(env_var: &str) -> Result<Arc<Keypair>, String> {
+    let value = env::var(env_var)
+        .map(Zeroizing::new)
+        .map_err(|_| format!("environment variable {env_var} is not set or not valid UTF-8"))?;
+    if let Ok(bytes) = bs58::decode(value.trim()).into_vec().map(Zeroizing::new)
+        && let Ok(keypair) = Keypair::try_from(bytes.as_slice())
+    {
+        return Ok(Arc::new(keypair));
+    }
+    read_keypair_file(value.as_str())
+        .map(Arc::new)
+        .map_err(|e| {
+            format!(
+                "environment variable {env_var} holds neither a base58 keypair nor the path of a \
+             readable keypair file: {e}"
+            )
+        })
+}
+
+/// Loads the keypair at `path`, falling back to the environment variable `env_var` as read by
+/// `parse_keypair_from_env` when the file cannot be read.
+pub fn keypair_from_path_or_env(path: &str, env_var: &str) -> Result<Arc<Keypair>, String> {
+    parse_keypair_from_path(path).or_else(|path_err| {
+        parse_keypair_from_env(env_var).map_err(|env_err| format!("{path_err}; {env_err}"))
+    })
+}
+
+// Directive accepted by `parse_keypair_or_generate` in place of a path
+pub const GENERATE_KEYPAIR_DIRECTIVE: &str = "new";
+
+/// Loads the keypair at `path`, or generates a fresh one when `path` is "new". The flag is
+/// true for a generated keypair, which the caller may want to persist.
+pub fn parse_keypair_or_generate(path: &str) -> Result<(Arc<Keypair>, bool), String> {
+    if path == GENERATE_KEYPAIR_DIRECTIVE {
+        Ok((Arc::new(Keypair::new()), true))
+    } else {
+        parse_keypair_from_path(path).map(|keypair| (keypair, false))
+    }
+}
+
 pub fn parse_pubkey_from_path(path: &str) -> Result<Pubkey, String> {
     read_keypair_file(path)
         .map(|keypair| keypair.pubkey())
         .map_err(|e| format!("failed to read keypair file '{path}': {e}"))
 }
 
+/// Parses a whole percentage from 0 to 100, given either bare as "50" or with a single
+/// trailing percent sign as "50%".
 pub fn parse_percentage(percentage: &str) -> Result<u8, String> {
     percentage
+        .strip_suffix('%')
+        .unwrap_or(percentage)
         .parse::<u8>()
         .map_err(|e| format!("Unable to parse input percentage, provided: {percentage}, err: {e}"))
         .and_then(|v| {
@@ -33,14 +87,488 @@ pub fn parse_percentage(percentage: &str) -> Result<u8, String> {
             }
         })
 }
+
+/// Parses a fractional percentage such as "12.5", from 0.0 to 100.0 inclusive.
+pub fn parse_percentage_f64(percentage: &str) -> Result<f64, String> {
+    percentage
+        .parse::<f64>()
+        .map_err(|e| format!("Unable to parse input percentage, provided: {percentage}, err: {e}"))
+        .and_then(|v| {
+            if (0.0..=100.0).contains(&v) {
+                Ok(v)
+            } else {
+                Err(format!(
+                    "Percentage must be in range of 0 to 100, provided: {v}"
+                ))
+            }
+        })
+}
+
+/// Parses a number and checks it against the bounds of `spec`, written as an interval such as
+/// `[0,100]`, with brackets for inclusive bounds and parentheses for exclusive ones, as in
+/// `(0,1)` or `[0,1)`. A bound left out, as in `[0,)`, does not limit that side.
+pub fn parse_bounded<T>(value: &str, spec: &str) -> Result<T, String>
+where
+    T: FromStr + PartialOrd + Display,
+    T::Err: Display,
+{
+    let invalid_spec =
+        || format!("Invalid bound spec '{spec}', expected one like [0,100] or (0,1)");
+    let lower_inclusive = match spec.chars().next() {
+        Some('[') => true,
+        Some('(') => false,
+        _ => return Err(invalid_spec()),
+    };
+    let upper_inclusive = match spec.chars().last() {
+        Some(']') => true,
+        Some(')') => false,
+        _ => return Err(invalid_spec()),
+    };
+    let (lower, upper) = spec
+        .get(1..spec.len() - 1)
+        .and_then(|bounds| bounds.split_once(','))
+        .ok_or_else(invalid_spec)?;
+    let parse_bound = |bound: &str| match bound.trim() {
+        "" => Ok(None),
+        bound => bound.parse::<T>().map(Some).map_err(|_| invalid_spec()),
+    };
+    let (lower, upper) = (parse_bound(lower)?, parse_bound(upper)?);
+
+    let number = value
+        .parse::<T>()
+        .map_err(|err| format!("Unable to parse input value, provided: {value}, err: {err}"))?;
+    let above_lower = lower.as_ref().is_none_or(|lower| {
+        if lower_inclusive {
+            number >= *lower
+        } else {
+            number > *lower
+        }
+    });
+    let below_upper = upper.as_ref().is_none_or(|upper| {
+        if upper_inclusive {
+            number <= *upper
+        } else {
+            number < *upper
+        }
+    });
+    if above_lower && below_upper {
+        return Ok(number);
+    }
+    let limits = [
+        lower.map(|lower| {
+            if lower_inclusive {
+                format!("at least {lower}")
+            } else {
+                format!("greater than {lower}")
+            }
+        }),
+        upper.map(|upper| {
+            if upper_inclusive {
+                format!("at most {upper}")
+            } else {
+                format!("less than {upper}")
+            }
+        }),
+    ];
+    Err(format!(
+        "Value must be {}, provided: {value}",
+        limits
+            .into_iter()
+            .flatten()
+            .collect::<Vec<_>>()
+            .join(" and ")
+    ))
+}
+
+/// Parses a slot, which may group its digits as in `123,456,789` or `123_456_789`.
 pub fn parse_slot(slot: &str) -> Result<Slot, String> {
-    parse_generic::<Slot, _>(slot)
+    parse_separated_integer::<Slot>(slot)
+}
+
+/// Parses a number of slots, given either bare or with a `slots` suffix as in `100slots`, so
+/// that slot counts can be told apart from durations where both are accepted.
+pub fn parse_slots(slots: &str) -> Result<u64, String> {
+    let digits_end = slots
+        .find(|c: char| !(c.is_ascii_digit() || c == '_' || c == ','))
+        .unwrap_or(slots.len());
+    let (count, unit) = slots.split_at(digits_end);
+    if count.is_empty() {
+        return Err(format!(
+            "error parsing '{slots}': expected a number of slots such as 100 or 100slots"
+        ));
+    }
+    match unit.trim_start() {
+        "" | "slot" | "slots" => parse_separated_integer::<u64>(count),
+        unit => Err(format!(
+            "error parsing '{slots}': unknown unit '{unit}', expected a number of slots such as \
+             100 or 100slots"
+        )),
+    }
+}
+
+/// The longest duration [`parse_duration`] accepts, 30 days.
+pub const MAX_DURATION: Duration = Duration::from_secs(30 * 24 * 60 * 60);
+
+/// Parses a duration given as a bare number of milliseconds, or as a number followed by `ms`,
+/// `s`, `m`, `h` or `d`, as in `400ms`, `2s` or `1h`. The number may have a fractional part,
+/// as in `6.25ms`. Zero and durations longer than [`MAX_DURATION`] are rejected.
+pub fn parse_duration(duration: &str) -> Result<Duration, String> {
+    let number_end = duration
+        .find(|c: char| !(c.is_ascii_digit() || c == '.'))
+        .unwrap_or(duration.len());
+    let (number, unit) = duration.split_at(number_end);
+    let unit_nanos: u128 = match unit.trim_start() {
+        "" | "ms" => 1_000_000,
+        "s" => 1_000_000_000,
+        "m" => 60 * 1_000_000_000,
+        "h" => 60 * 60 * 1_000_000_000,
+        "d" => 24 * 60 * 60 * 1_000_000_000,
+        unit => {
+            return Err(format!(
+                "error parsing '{duration}': unknown unit '{unit}', expected ms, s, m, h or d"
+            ));
+        }
+    };
+    let (whole, fraction) = number.split_once('.').unwrap_or((number, ""));
+    if whole.is_empty() && fraction.is_empty() {
+        return Err(format!(
+            "error parsing '{duration}': expected a duration such as 400ms, 2s or 1h"
+        ));
+    }
+    let parse_digits = |digits: &str| {
+        if digits.is_empty() {
+            return Ok(0);
+        }
+        digits
+            .parse::<u128>()
+            .map_err(|err| format!("error parsing '{duration}': {err}"))
+    };
+    let too_long = || format!("'{duration}' is longer than the 30 day maximum");
+    let fraction_nanos = parse_digits(fraction)?
+        .checked_mul(unit_nanos)
+        .zip(10u128.checked_pow(fraction.len() as u32))
+        .map(|(fraction_nanos, scale)| fraction_nanos / scale)
+        .ok_or_else(|| format!("error parsing '{duration}': too many fractional digits"))?;
+    let nanos = parse_digits(whole)?
+        .checked_mul(unit_nanos)
+        .and_then(|whole_nanos| whole_nanos.checked_add(fraction_nanos))
+        .ok_or_else(too_long)?;
+    if nanos == 0 {
+        return Err(format!("'{duration}' is not a positive duration"));
+    }
+    if nanos > MAX_DURATION.as_nanos() {
+        return Err(too_long());
+    }
+    Ok(Duration::from_nanos(nanos as u64))
+}
+
+/// Parses an epoch, which may group its digits as in `1,234` or `1_234`.
+pub fn parse_epoch(epoch: &str) -> Result<Epoch, String> {
+    parse_separated_integer::<Epoch>(epoch)
+}
+
+/// Returns the epoch that `slot` falls in under `epoch_schedule`.
+pub fn epoch_from_slot(slot: Slot, epoch_schedule: &EpochSchedule) -> Epoch {
+    epoch_schedule.get_epoch(slot)
+}
+
+/// Returns the first slot of `epoch` under `epoch_schedule`, taking the shorter warmup
+/// epochs into account.
+pub fn first_slot_in_epoch(epoch: Epoch, epoch_schedule: &EpochSchedule) -> Slot {
+    epoch_schedule.get_first_slot_in_epoch(epoch)
 }
 
 pub fn parse_pubkey(pubkey: &str) -> Result<Pubkey, String> {
     parse_generic::<Pubkey, _>(pubkey).or_else(|_| parse_pubkey_from_path(pubkey))
 }
 
+/// Parses a pubkey like [`parse_pubkey`], or `none` or `null` for no pubkey, as for an
+/// authority that is to be left unset.
+pub fn parse_pubkey_or_none(value: &str) -> Result<Option<Pubkey>, String> {
+    match value {
+        "none" | "null" => Ok(None),
+        value => parse_pubkey(value).map(Some),
+    }
+}
+
+/// The largest compute unit limit a transaction can request.
+pub const MAX_COMPUTE_UNIT_LIMIT: u32 = 1_400_000;
+
+/// The compute unit limit a single instruction gets when none is requested.
+pub const DEFAULT_COMPUTE_UNIT_LIMIT: u32 = 200_000;
+
+/// Parses a compute unit price, in micro-lamports, that sets the transaction priority fee.
+pub fn parse_priority_fee(fee: &str) -> Result<u64, String> {
+    parse_separated_integer::<u64>(fee)
+}
+
+/// Compute unit prices above this many micro-lamports, a whole lamport per compute unit, are
+/// most likely lamports given by mistake.
+pub const COMPUTE_UNIT_PRICE_WARNING_THRESHOLD: u64 = 1_000_000;
+
+/// Parses a compute unit price in micro-lamports. Prices above
+/// [`COMPUTE_UNIT_PRICE_WARNING_THRESHOLD`] are accepted, callers warn about them with
+/// [`compute_unit_price_warning`] once parsing succeeded.
+pub fn parse_compute_unit_price(price: &str) -> Result<u64, String> {
+    parse_separated_integer::<u64>(price)
+}
+
+/// Returns the warning to show for a compute unit price above
+/// [`COMPUTE_UNIT_PRICE_WARNING_THRESHOLD`], if it is.
+pub fn compute_unit_price_warning(price: u64) -> Option<String> {
+    (price > COMPUTE_UNIT_PRICE_WARNING_THRESHOLD).then(|| {
+        format!(
+            "a compute unit price of {price} micro-lamports is over 1 lamport per compute \
+             unit, was it meant to be in lamports?"
+        )
+    })
+}
+
+pub fn parse_compute_unit_limit(limit: &str) -> Result<u32, String> {
+    parse_separated_integer::<u32>(limit).and_then(|v| {
+        if v > MAX_COMPUTE_UNIT_LIMIT {
+            Err(format!(
+                "Compute unit limit must be in range of 0 to {MAX_COMPUTE_UNIT_LIMIT}, provided: {v}"
+            ))
+        } else {
+            Ok(v)
+        }
+    })
+}
+
+pub fn priority_fee_arg() -> Arg {
+    Arg::new("priority_fee")
+        .long("with-compute-unit-price")
+        .value_name("MICROLAMPORTS")
+        .value_parser(parse_priority_fee)
+        .default_value("0")
+        .help(
+            "Price of each compute unit in micro-lamports, which sets the transaction priority fee",
+        )
+}
+
+/// Name of the argument built by [`compute_unit_price_arg`]. Pass its value to
+/// [`compute_unit_price_warning`] after parsing.
+pub const MAX_COMPUTE_UNIT_PRICE_ARG: &str = "max_compute_unit_price";
+
+pub fn compute_unit_price_arg() -> Arg {
+    Arg::new(MAX_COMPUTE_UNIT_PRICE_ARG)
+        .long("max-compute-unit-price")
+        .value_name("MICROLAMPORTS")
+        .value_parser(parse_compute_unit_price)
+        .help(
+            "Highest price to pay per compute unit, in micro-lamports (1 lamport is 1,000,000 \
+             micro-lamports)",
+        )
+}
+
+pub fn compute_unit_limit_arg() -> Arg {
+    Arg::new("compute_unit_limit")
+        .long("with-compute-unit-limit")
+        .value_name("COMPUTE_UNITS")
+        .value_parser(parse_compute_unit_limit)
+        .default_value(DEFAULT_COMPUTE_UNIT_LIMIT.to_string())
+        .help(format!(
+            "Compute unit limit of the transaction, in range of 0 to {MAX_COMPUTE_UNIT_LIMIT}"
+        ))
+}
+
+/// Parses a decimal token amount such as "1.5" into its on-chain integer representation with
+/// `decimals` decimal places. Amounts with more decimal places than that, which would lose
+/// precision, and amounts overflowing u64 are rejected.
+pub fn parse_token_amount(amount: &str, decimals: u8) -> Result<u64, String> {
+    let (whole, fraction) = amount.split_once('.').unwrap_or((amount, ""));
+    let is_digits = |s: &str| s.bytes().all(|b| b.is_ascii_digit());
+    if (whole.is_empty() && fraction.is_empty()) || !is_digits(whole) || !is_digits(fraction) {
+        return Err(format!("Unable to parse token amount, provided: {amount}"));
+    }
+    if fraction.len() > decimals as usize {
+        return Err(format!(
+            "Token amount {amount} has more than {decimals} decimal places"
+        ));
+    }
+    let overflow = || format!("Token amount {amount} is too large");
+    let scale = 10u64.checked_pow(decimals.into()).ok_or_else(overflow)?;
+    let whole = match whole {
+        "" => 0,
+        whole => whole.parse::<u64>().map_err(|_| overflow())?,
+    };
+    let fraction = match fraction {
+        "" => 0,
+        // Cannot overflow as scale fits in u64 and fraction is shorter than it.
+        fraction => {
+            fraction.parse::<u64>().unwrap()
+                * 10u64.pow(u32::from(decimals) - fraction.len() as u32)
+        }
+    };
+    whole
+        .checked_mul(scale)
+        .and_then(|amount| amount.checked_add(fraction))
+        .ok_or_else(overflow)
+}
+
+/// Returns a clap value parser for a token amount with `decimals` decimal places, naming the
+/// argument `name` in its errors.
+pub fn parse_token_amount_arg(
+    name: &str,
+    decimals: u8,
+) -> impl Fn(&str) -> Result<u64, String> + Clone + Send + Sync + 'static {
+    let name = name.to_string();
+    move |amount| parse_token_amount(amount, decimals).map_err(|err| format!("{name}: {err}"))
+}
+
+/// Memo size limit of [`memo_arg`], well below what fits in a transaction next to its other
+/// instructions.
+pub const DEFAULT_MEMO_MAX_BYTES: usize = 200;
+
+/// Returns a clap value parser for a memo of 1 to `max_bytes` bytes, returned unchanged. The
+/// memo program takes any UTF-8 string, which every `&str` is. The limit is counted in
+/// bytes, not chars, since that is what takes up space in the transaction.
+pub fn parse_memo(
+    max_bytes: usize,
+) -> impl Fn(&str) -> Result<String, String> + Clone + Send + Sync + 'static {
+    move |memo| {
+        if memo.is_empty() {
+            Err("Memo cannot be empty".to_string())
+        } else if memo.len() > max_bytes {
+            Err(format!(
+                "Memo is {} bytes long, more than the limit of {max_bytes} bytes",
+                memo.len()
+            ))
+        } else {
+            Ok(memo.to_string())
+        }
+    }
+}
+
+/// Name of the argument built by [`memo_arg`].
+pub const MEMO_ARG: &str = "memo";
+
+pub fn memo_arg() -> Arg {
+    Arg::new(MEMO_ARG)
+        .long("with-memo")
+        .value_name("MEMO")
+        .value_parser(parse_memo(DEFAULT_MEMO_MAX_BYTES))
+        .help(format!(
+            "Memo to attach to the transaction, up to {DEFAULT_MEMO_MAX_BYTES} bytes of UTF-8"
+        ))
+}
+
+pub fn parse_blockhash(blockhash: &str) -> Result<Hash, String> {
+    parse_hash(blockhash)
+}
+
+/// Parses a base58 encoded hash, such as an expected genesis hash, rejecting strings that do
+/// not decode to exactly 32 bytes.
+pub fn parse_hash(hash: &str) -> Result<Hash, String> {
+    hash.parse::<Hash>().map_err(|err| match err {
+        ParseHashError::WrongSize => {
+            format!("Invalid hash '{hash}': it does not decode to exactly {HASH_BYTES} bytes")
+        }
+        ParseHashError::Invalid => format!("Invalid hash '{hash}': it is not valid base58"),
+    })
+}
+
+/// Parses the base58 genesis hash a cluster is identified by.
+pub fn parse_genesis_hash(genesis_hash: &str) -> Result<Hash, String> {
+    parse_hash(genesis_hash)
+}
+
+/// Parses a `host:port` address, resolving host names. With `no_wildcard` set, addresses
+/// resolving to `0.0.0.0` or `[::]` are rejected.
+pub fn parse_network_address(address: &str, no_wildcard: bool) -> Result<SocketAddr, String> {
+    let socket_addr = address
+        .to_socket_addrs()
+        .map_err(|e| format!("failed to resolve network address '{address}': {e}"))?
+        .next()
+        .ok_or_else(|| format!("network address '{address}' did not resolve to any address"))?;
+    check_wildcard(&socket_addr.ip(), address, no_wildcard)?;
+    Ok(socket_addr)
+}
+
+/// Parses an IP address. With `no_wildcard` set, `0.0.0.0` and `::` are rejected.
+pub fn parse_ip_address(ip: &str, no_wildcard: bool) -> Result<IpAddr, String> {
+    let ip_addr = parse_generic::<IpAddr, _>(ip)?;
+    check_wildcard(&ip_addr, ip, no_wildcard)?;
+    Ok(ip_addr)
+}
+
+fn check_wildcard(ip: &IpAddr, input: &str, no_wildcard: bool) -> Result<(), String> {
+    if no_wildcard && ip.is_unspecified() {
+        Err(format!("wildcard address '{input}' is not allowed"))
+    } else {
+        Ok(())
+    }
+}
+
+/// Groups flat values into triples, parsing each value with `parser`.
+pub fn parse_triples<T, F>(values: &[String], parser: F) -> Result<Vec<[T; 3]>, String>
+where
+    F: Fn(&str) -> Result<T, String>,
+{
+    if !values.len().is_multiple_of(3) {
+        return Err(format!(
+            "expected values in groups of three, but {} values were given",
+            values.len()
+        ));
+    }
+    values
+        .chunks_exact(3)
+        .map(|triple| {
+            Ok([
+                parser(&triple[0])?,
+                parser(&triple[1])?,
+                parser(&triple[2])?,
+            ])
+        })
+        .collect()
+}
+
+/// Names of the supported mnemonic languages.
+pub const POSSIBLE_LANGUAGES: &[&str] = &[
+    "english",
+    "chinese-simplified",
+    "chinese-traditional",
+    "japanese",
+    "spanish",
+    "korean",
+    "french",
+    "italian",
+];
+
+/// Returns the mnemonic language named `name`, one of `POSSIBLE_LANGUAGES`.
+pub fn language_from_name(name: &str) -> Option<Language> {
+    match name {
+        "english" => Some(Language::English),
+        "chinese-simplified" => Some(Language::ChineseSimplified),
+        "chinese-traditional" => Some(Language::ChineseTraditional),
+        "japanese" => Some(Language::Japanese),
+        "spanish" => Some(Language::Spanish),
+        "korean" => Some(Language::Korean),
+        "french" => Some(Language::French),
+        "italian" => Some(Language::Italian),
+        _ => None,
+    }
+}
+
+/// Parses a comma separated list of mnemonic language names such as "english,japanese",
+/// keeping their order and dropping repeats.
+pub fn parse_languages(languages: &str) -> Result<Vec<Language>, String> {
+    let mut parsed = Vec::new();
+    for name in languages.split(',').map(str::trim) {
+        let language = language_from_name(name).ok_or_else(|| {
+            format!(
+                "Unknown language '{name}', expected a comma separated list of: {}",
+                POSSIBLE_LANGUAGES.join(", ")
+            )
+        })?;
+        if !parsed.contains(&language) {
+            parsed.push(language);
+        }
+    }
+    Ok(parsed)
+}
+
 fn parse_generic<U, T>(string: T) -> Result<U, String>
 where
     T: AsRef<str> + Display,
@@ -53,8 +581,469 @@ where
         .map_err(|err| format!("error parsing '{string}': {err}"))
 }
 
+/// Parses an integer whose digits may be grouped with `_` or `,`. A separator must sit
+/// between two digits, so inputs like `12,,3`, `,123` or `1_` are rejected.
+fn parse_separated_integer<U>(integer: &str) -> Result<U, String>
+where
+    U: FromStr,
+    U::Err: Display,
+{
+    let bytes = integer.as_bytes();
+    let misplaced_separator = bytes.iter().enumerate().any(|(i, byte)| {
+        matches!(byte, b'_' | b',')
+            && !(i > 0
+                && bytes[i - 1].is_ascii_digit()
+                && bytes.get(i + 1).is_some_and(u8::is_ascii_digit))
+    });
+    if misplaced_separator {
+        return Err(format!(
+            "error parsing '{integer}': digit separators must sit between two digits"
+        ));
+    }
+    integer
+        .replace(['_', ','], "")
+        .parse::<U>()
+        .map_err(|err| format!("error parsing '{integer}': {err}"))
+}
+
 pub fn unix_timestamp_from_rfc3339_datetime(value: &str) -> Result<UnixTimestamp, String> {
     DateTime::parse_from_rfc3339(value)
         .map(|date_time| date_time.timestamp())
         .map_err(|e| format!("failed parsing date '{value}': {e}"))
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_compute_unit_price() {
+        assert_eq!(parse_compute_unit_price("0"), Ok(0));
+        assert_eq!(parse_compute_unit_price("1_000_000"), Ok(1_000_000));
+        assert_eq!(parse_compute_unit_price("5000000"), Ok(5_000_000));
+        assert!(parse_compute_unit_price("-1").is_err());
+        assert!(parse_compute_unit_price("0.5").is_err());
+
+        assert_eq!(
+            compute_unit_price_warning(COMPUTE_UNIT_PRICE_WARNING_THRESHOLD),
+            None
+        );
+        assert!(
+            compute_unit_price_warning(COMPUTE_UNIT_PRICE_WARNING_THRESHOLD + 1)
+                .unwrap()
+                .contains("1000001 micro-lamports")
+        );
+    }
+
+    #[test]
+    fn test_parse_hash() {
+        let hash = Hash::new_unique();
+        assert_eq!(parse_hash(&hash.to_string()), Ok(hash));
+        assert_eq!(parse_blockhash(&hash.to_string()), Ok(hash));
+        assert!(parse_hash("").is_err());
+        assert!(parse_blockhash("not a hash").is_err());
+    }
+
+    #[test]
+    fn test_parse_percentage_f64() {
+        assert_eq!(parse_percentage_f64("0"), Ok(0.0));
+        assert_eq!(parse_percentage_f64("0.0"), Ok(0.0));
+        assert_eq!(parse_percentage_f64("12.5"), Ok(12.5));
+        assert_eq!(parse_percentage_f64("100"), Ok(100.0));
+        assert_eq!(parse_percentage_f64("100.0"), Ok(100.0));
+        assert_eq!(
+            parse_percentage_f64("100.0001"),
+            Err("Percentage must be in range of 0 to 100, provided: 100.0001".to_string())
+        );
+        assert!(parse_percentage_f64("100.1").is_err());
+        assert!(parse_percentage_f64("-0.5").is_err());
+        assert!(parse_percentage_f64("NaN").is_err());
+        assert!(parse_percentage_f64("half").is_err());
+    }
+
+    #[test]
+    fn test_parse_ip_address() {
+        assert_eq!(
+            parse_ip_address("127.0.0.1", true),
+            Ok(IpAddr::from([127, 0, 0, 1]))
+        );
+        assert!(parse_ip_address("::1", true).is_ok());
+        assert!(parse_ip_address("0.0.0.0", false).is_ok());
+        assert_eq!(
+            parse_ip_address("0.0.0.0", true),
+            Err("wildcard address '0.0.0.0' is not allowed".to_string())
+        );
+        assert!(parse_ip_address("::", true).is_err());
+        assert!(parse_ip_address("127.0.0.1:8001", false).is_err());
+        assert!(parse_ip_address("256.0.0.1", false).is_err());
+    }
+
+    #[test]
+    fn test_parse_network_address() {
+        assert_eq!(
+            parse_network_address("127.0.0.1:8001", true),
+            Ok(SocketAddr::from(([127, 0, 0, 1], 8001)))
+        );
+        assert!(parse_network_address("[::1]:8001", true).is_ok());
+        assert!(parse_network_address("0.0.0.0:8001", false).is_ok());
+        assert_eq!(
+            parse_network_address("0.0.0.0:8001", true),
+            Err("wildcard address '0.0.0.0:8001' is not allowed".to_string())
+        );
+        assert!(parse_network_address("[::]:8001", true).is_err());
+        assert!(parse_network_address("127.0.0.1", false).is_err());
+        assert!(parse_network_address("127.0.0.1:port", false).is_err());
+    }
+
+    #[test]
+    fn test_parse_triples() {
+        let pubkeys = [(); 6].map(|_| Pubkey::new_unique());
+        let values = pubkeys.map(|pubkey| pubkey.to_string());
+        assert_eq!(
+            parse_triples(&values, parse_pubkey),
+            Ok(vec![
+                [pubkeys[0], pubkeys[1], pubkeys[2]],
+                [pubkeys[3], pubkeys[4], pubkeys[5]],
+            ])
+        );
+        assert_eq!(parse_triples(&[], parse_pubkey), Ok(vec![]));
+        assert_eq!(
+            parse_triples(&values[..4], parse_pubkey),
+            Err("expected values in groups of three, but 4 values were given".to_string())
+        );
+        let mut values = values.to_vec();
+        values[4] = "not-a-pubkey".to_string();
+        assert!(parse_triples(&values, parse_pubkey).is_err());
+    }
+
+    #[test]
+    fn test_parse_compute_unit_limit() {
+        assert_eq!(parse_priority_fee("0"), Ok(0));
+        assert_eq!(parse_priority_fee("1,000"), Ok(1000));
+        assert!(parse_priority_fee("-1").is_err());
+
+        assert_eq!(parse_compute_unit_limit("0"), Ok(0));
+        assert_eq!(
+            parse_compute_unit_limit("1_400_000"),
+            Ok(MAX_COMPUTE_UNIT_LIMIT)
+        );
+        assert_eq!(
+            parse_compute_unit_limit("1400001"),
+            Err(
+                "Compute unit limit must be in range of 0 to 1400000, provided: 1400001"
+                    .to_string()
+            )
+        );
+
+        let matches = clap::Command::new("test")
+            .arg(priority_fee_arg())
+            .arg(compute_unit_limit_arg())
+            .try_get_matches_from(["test"])
+            .unwrap();
+        assert_eq!(matches.get_one::<u64>("priority_fee"), Some(&0));
+        assert_eq!(
+            matches.get_one::<u32>("compute_unit_limit"),
+            Some(&DEFAULT_COMPUTE_UNIT_LIMIT)
+        );
+    }
+
+    #[test]
+    fn test_parse_hash_errors() {
+        assert_eq!(
+            parse_hash("1111"),
+            Err("Invalid hash '1111': it does not decode to exactly 32 bytes".to_string())
+        );
+        assert_eq!(
+            parse_hash("0OIl"),
+            Err("Invalid hash '0OIl': it is not valid base58".to_string())
+        );
+        let genesis_hash = Hash::new_unique();
+        assert_eq!(
+            parse_genesis_hash(&genesis_hash.to_string()),
+            Ok(genesis_hash)
+        );
+    }
+
+    #[test]
+    fn test_parse_token_amount() {
+        assert_eq!(parse_token_amount("1.5", 9), Ok(1_500_000_000));
+        assert_eq!(parse_token_amount("1", 0), Ok(1));
+        assert_eq!(parse_token_amount(".5", 2), Ok(50));
+        assert_eq!(parse_token_amount("5.", 2), Ok(500));
+        assert_eq!(parse_token_amount("0.000001", 6), Ok(1));
+        assert_eq!(parse_token_amount("18446744073709551615", 0), Ok(u64::MAX));
+        assert_eq!(
+            parse_token_amount("0.1", 0),
+            Err("Token amount 0.1 has more than 0 decimal places".to_string())
+        );
+        assert_eq!(
+            parse_token_amount("18446744073709551616", 0),
+            Err("Token amount 18446744073709551616 is too large".to_string())
+        );
+        assert!(parse_token_amount("18446744073709.551616", 6).is_err());
+        assert!(parse_token_amount("1", 20).is_err());
+        for invalid in ["", ".", "-1", "1.2.3", "1e3", " 1"] {
+            assert!(parse_token_amount(invalid, 9).is_err(), "{invalid}");
+        }
+
+        let parser = parse_token_amount_arg("--amount", 2);
+        assert_eq!(parser("1.25"), Ok(125));
+        assert_eq!(
+            parser("1.234"),
+            Err("--amount: Token amount 1.234 has more than 2 decimal places".to_string())
+        );
+    }
+
+    #[test]
+    fn test_parse_keypair_or_generate() {
+        let (_, generated) = parse_keypair_or_generate(GENERATE_KEYPAIR_DIRECTIVE).unwrap();
+        assert!(generated);
+
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("id.json");
+        let keypair = Keypair::new();
+        solana_keypair::write_keypair_file(&keypair, &path).unwrap();
+        let (loaded, generated) = parse_keypair_or_generate(path.to_str().unwrap()).unwrap();
+        assert!(!generated);
+        assert_eq!(loaded.pubkey(), keypair.pubkey());
+
+        assert!(
+            parse_keypair_or_generate(dir.path().join("missing.json").to_str().unwrap()).is_err()
+        );
+    }
+
+    #[test]
+    fn test_parse_percentage() {
+        assert_eq!(parse_percentage("0"), Ok(0));
+        assert_eq!(parse_percentage("50"), Ok(50));
+        assert_eq!(parse_percentage("50%"), Ok(50));
+        assert_eq!(parse_percentage("100%"), Ok(100));
+        assert_eq!(
+            parse_percentage("101%"),
+            Err("Percentage must be in range of 0 to 100, provided: 101".to_string())
+        );
+        for invalid in ["%", "50%%", "%50", "50 %", "-1"] {
+            assert!(parse_percentage(invalid).is_err(), "{invalid}");
+        }
+    }
+
+    #[test]
+    fn test_parse_keypair_from_env() {
+        let keypair = Keypair::new();
+        let var = "SOLARIUM_CLAP_UTILS_TEST_BASE58_KEYPAIR";
+        // SAFETY: the variable is only used by this test.
+        unsafe { env::set_var(var, keypair.to_base58_string()) };
+        assert_eq!(
+            parse_keypair_from_env(var).unwrap().pubkey(),
+            keypair.pubkey()
+        );
+        assert_eq!(
+            keypair_from_path_or_env("/nonexistent/id.json", var)
+                .unwrap()
+                .pubkey(),
+            keypair.pubkey()
+        );
+
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("id.json");
+        solana_keypair::write_keypair_file(&keypair, &path).unwrap();
+        let var = "SOLARIUM_CLAP_UTILS_TEST_KEYPAIR_PATH";
+        // SAFETY: the variable is only used by this test.
+        unsafe { env::set_var(var, &path) };
+        assert_eq!(
+            parse_keypair_from_env(var).unwrap().pubkey(),
+            keypair.pubkey()
+        );
+
+        let var = "SOLARIUM_CLAP_UTILS_TEST_INVALID_KEYPAIR";
+        // SAFETY: the variable is only used by this test.
+        unsafe { env::set_var(var, "secret-value") };
+        let err = parse_keypair_from_env(var).unwrap_err();
+        assert!(!err.contains("secret-value"), "{err}");
+        assert_eq!(
+            parse_keypair_from_env("SOLARIUM_CLAP_UTILS_TEST_UNSET").unwrap_err(),
+            "environment variable SOLARIUM_CLAP_UTILS_TEST_UNSET is not set or not valid UTF-8"
+        );
+    }
+
+    #[test]
+    fn test_parse_languages() {
+        assert_eq!(
+            parse_languages("english, japanese,english"),
+            Ok(vec![Language::English, Language::Japanese])
+        );
+        for name in POSSIBLE_LANGUAGES {
+            assert!(language_from_name(name).is_some(), "{name}");
+        }
+        assert!(parse_languages("english,klingon").is_err());
+        assert!(parse_languages("").is_err());
+    }
+
+    #[test]
+    fn test_epoch_helpers() {
+        assert_eq!(parse_epoch("1,234"), Ok(1234));
+        assert!(parse_epoch("-1").is_err());
+
+        // The default schedule warms up with epochs of 32, 64, 128... slots.
+        let epoch_schedule = EpochSchedule::default();
+        assert_eq!(epoch_from_slot(0, &epoch_schedule), 0);
+        assert_eq!(epoch_from_slot(31, &epoch_schedule), 0);
+        assert_eq!(epoch_from_slot(32, &epoch_schedule), 1);
+        assert_eq!(first_slot_in_epoch(1, &epoch_schedule), 32);
+        assert_eq!(first_slot_in_epoch(2, &epoch_schedule), 96);
+
+        let epoch_schedule = EpochSchedule::without_warmup();
+        let epoch = epoch_from_slot(1_000_000, &epoch_schedule);
+        assert!(first_slot_in_epoch(epoch, &epoch_schedule) <= 1_000_000);
+        assert!(first_slot_in_epoch(epoch + 1, &epoch_schedule) > 1_000_000);
+    }
+
+    #[test]
+    fn test_parse_slot_separators() {
+        assert_eq!(parse_slot("123456789"), Ok(123_456_789));
+        assert_eq!(parse_slot("123,456,789"), Ok(123_456_789));
+        assert_eq!(parse_slot("123_456_789"), Ok(123_456_789));
+        assert_eq!(parse_epoch("1_000"), Ok(1000));
+        for invalid in [",123", "123,", "12,,3", "1_", "_1", "12_,3", "1 000", ""] {
+            assert!(parse_slot(invalid).is_err(), "{invalid}");
+        }
+        assert_eq!(
+            parse_slot("12,,3"),
+            Err("error parsing '12,,3': digit separators must sit between two digits".to_string())
+        );
+    }
+
+    #[test]
+    fn test_parse_slots() {
+        assert_eq!(parse_slots("100"), Ok(100));
+        assert_eq!(parse_slots("100slots"), Ok(100));
+        assert_eq!(parse_slots("100 slots"), Ok(100));
+        assert_eq!(parse_slots("1slot"), Ok(1));
+        assert_eq!(parse_slots("1_000slots"), Ok(1000));
+        assert_eq!(
+            parse_slots("100ms"),
+            Err(
+                "error parsing '100ms': unknown unit 'ms', expected a number of slots such as 100 \
+                 or 100slots"
+                    .to_string()
+            )
+        );
+        assert!(parse_slots("slots").is_err());
+        assert!(parse_slots("").is_err());
+        assert!(parse_slots("1,slots").is_err());
+    }
+
+    #[test]
+    fn test_parse_pubkey_or_none() {
+        let pubkey = Pubkey::new_unique();
+        assert_eq!(parse_pubkey_or_none(&pubkey.to_string()), Ok(Some(pubkey)));
+        assert_eq!(parse_pubkey_or_none("none"), Ok(None));
+        assert_eq!(parse_pubkey_or_none("null"), Ok(None));
+        assert!(parse_pubkey_or_none("None").is_err());
+        assert!(parse_pubkey_or_none("").is_err());
+    }
+
+    #[test]
+    fn test_parse_memo() {
+        let parser = parse_memo(5);
+        assert_eq!(parser("hello"), Ok("hello".to_string()));
+        assert_eq!(parser(""), Err("Memo cannot be empty".to_string()));
+        // 5 chars but 6 bytes.
+        assert_eq!(
+            parser("héllo"),
+            Err("Memo is 6 bytes long, more than the limit of 5 bytes".to_string())
+        );
+
+        let command = clap::Command::new("test").arg(memo_arg());
+        let memo = "m".repeat(DEFAULT_MEMO_MAX_BYTES);
+        let matches = command
+            .clone()
+            .try_get_matches_from(["test", "--with-memo", &memo])
+            .unwrap();
+        assert_eq!(matches.get_one::<String>(MEMO_ARG), Some(&memo));
+        assert!(
+            command
+                .try_get_matches_from(["test", "--with-memo", &format!("{memo}m")])
+                .is_err()
+        );
+    }
+
+    #[test]
+    fn test_parse_bounded() {
+        assert_eq!(parse_bounded::<u8>("0", "[0,100]"), Ok(0));
+        assert_eq!(parse_bounded::<u8>("100", "[0,100]"), Ok(100));
+        assert_eq!(parse_bounded::<u64>("5", "[1,)"), Ok(5));
+        assert_eq!(parse_bounded::<i64>("-5", "(,0)"), Ok(-5));
+        assert_eq!(parse_bounded::<f64>("0.5", "(0, 1)"), Ok(0.5));
+        assert_eq!(
+            parse_bounded::<f64>("0", "(0,1)"),
+            Err("Value must be greater than 0 and less than 1, provided: 0".to_string())
+        );
+        assert_eq!(
+            parse_bounded::<f64>("1", "[0,1)"),
+            Err("Value must be at least 0 and less than 1, provided: 1".to_string())
+        );
+        assert_eq!(
+            parse_bounded::<u8>("0", "[1,)"),
+            Err("Value must be at least 1, provided: 0".to_string())
+        );
+        assert!(parse_bounded::<u8>("256", "[0,)").is_err());
+        assert!(parse_bounded::<u8>("x", "[0,1]").is_err());
+        for spec in [
+            "", "[]", "0,100", "[0,100", "{0,100}", "[0;100]", "[a,1]", "[0,1,2]",
+        ] {
+            assert_eq!(
+                parse_bounded::<u8>("1", spec),
+                Err(format!(
+                    "Invalid bound spec '{spec}', expected one like [0,100] or (0,1)"
+                )),
+                "{spec}"
+            );
+        }
+    }
+
+    #[test]
+    fn test_parse_duration() {
+        assert_eq!(parse_duration("400"), Ok(Duration::from_millis(400)));
+        assert_eq!(parse_duration("400ms"), Ok(Duration::from_millis(400)));
+        assert_eq!(parse_duration("6.25ms"), Ok(Duration::from_micros(6250)));
+        assert_eq!(parse_duration("2s"), Ok(Duration::from_secs(2)));
+        assert_eq!(parse_duration("2 s"), Ok(Duration::from_secs(2)));
+        assert_eq!(parse_duration("1.5s"), Ok(Duration::from_millis(1500)));
+        assert_eq!(parse_duration(".5s"), Ok(Duration::from_millis(500)));
+        assert_eq!(parse_duration("1m"), Ok(Duration::from_secs(60)));
+        assert_eq!(parse_duration("1h"), Ok(Duration::from_secs(3600)));
+        assert_eq!(parse_duration("1d"), Ok(Duration::from_secs(86400)));
+        assert_eq!(parse_duration("0.000001ms"), Ok(Duration::from_nanos(1)));
+
+        assert_eq!(parse_duration("30d"), Ok(MAX_DURATION));
+        assert_eq!(parse_duration("720h"), Ok(MAX_DURATION));
+        for too_long in [
+            "30.000001d",
+            "31d",
+            "99999999999999999999999999999999999999d",
+        ] {
+            assert_eq!(
+                parse_duration(too_long),
+                Err(format!("'{too_long}' is longer than the 30 day maximum")),
+                "{too_long}"
+            );
+        }
+
+        assert_eq!(
+            parse_duration("0s"),
+            Err("'0s' is not a positive duration".to_string())
+        );
+        assert_eq!(
+            parse_duration("0.0000001ms"),
+            Err("'0.0000001ms' is not a positive duration".to_string())
+        );
+        assert_eq!(
+            parse_duration("2w"),
+            Err("error parsing '2w': unknown unit 'w', expected ms, s, m, h or d".to_string())
+        );
+        for invalid in ["", ".", "ms", "-1s", "1.2.3s", "1e3"] {
+            assert!(parse_duration(invalid).is_err(), "{invalid}");
+        }
+    }
+}
@@ -0,0 +1,288 @@
+//! JSON form of a [`GenesisConfig`] for `genesis export` and `genesis import`, also written
+//! and read as TOML.
+//!
+//! Pubkeys are base58 strings and lamports are decimal strings, so that JSON consumers that
+//! read numbers as doubles cannot lose precision, and TOML, whose integers are signed 64 bit,
+//! can hold any balance. Account data is base64.
+
+use base64::Engine;
+use base64::prelude::BASE64_STANDARD;
+use serde::{Deserialize, Serialize};
+use solana_account::Account;
+use solana_clock::UnixTimestamp;
+use solana_cluster_type::ClusterType;
+use solana_epoch_schedule::EpochSchedule;
+use solana_fee_calculator::FeeRateGovernor;
+use solana_genesis_config::GenesisConfig;
+use solana_inflation::Inflation;
+use solana_poh_config::PohConfig;
+use solana_pubkey::Pubkey;
+use solana_rent::Rent;
+use std::collections::BTreeMap;
+
+#[derive(Debug, Serialize, Deserialize)]
+pub(crate) struct GenesisConfigJson {
+    creation_time: UnixTimestamp,
+    accounts: BTreeMap<String, AccountJson>,
+    native_instruction_processors: Vec<(String, String)>,
+    rewards_pools: BTreeMap<String, AccountJson>,
+    ticks_per_slot: u64,
+    poh_config: PohConfig,
+    fee_rate_governor: FeeRateGovernorJson,
+    rent: RentJson,
+    inflation: Inflation,
+    epoch_schedule: EpochSchedule,
+    cluster_type: ClusterType,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+struct AccountJson {
+    lamports: String,
+    data: String,
+    owner: String,
+    executable: bool,
+    rent_epoch: String,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+struct FeeRateGovernorJson {
+    lamports_per_signature: String,
+    target_lamports_per_signature: String,
+    target_signatures_per_slot: u64,
+    min_lamports_per_signature: String,
+    max_lamports_per_signature: String,
+    burn_percent: u8,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+struct RentJson {
+    lamports_per_byte_year: String,
+    exemption_threshold: f64,
+    burn_percent: u8,
+}
+
+impl From<&GenesisConfig> for GenesisConfigJson {
+    fn from(genesis_config: &GenesisConfig) -> Self {
+        let accounts_json = |accounts: &BTreeMap<Pubkey, Account>| {
+            accounts
+                .iter()
+                .map(|(pubkey, account)| (pubkey.to_string(), AccountJson::from(account)))
+                .collect()
+        };
+        let FeeRateGovernor {
+            lamports_per_signature,
+            target_lamports_per_signature,
+            target_signatures_per_slot,
+            min_lamports_per_signature,
+            max_lamports_per_signature,
+            burn_percent,
+        } = genesis_config.fee_rate_governor.clone();
+        Self {
+            creation_time: genesis_config.creation_time,
+            accounts: accounts_json(&genesis_config.accounts),
+            native_instruction_processors: genesis_config
+                .native_instruction_processors
+                .iter()
+                .map(|(name, program_id)| (name.clone(), program_id.to_string()))
+                .collect(),
+            rewards_pools: accounts_json(&genesis_config.rewards_pools),
+            ticks_per_slot: genesis_config.ticks_per_slot,
+            poh_config: genesis_config.poh_config.clone(),
+            fee_rate_governor: FeeRateGovernorJson {
+                lamports_per_signature: lamports_per_signature.to_string(),
+                target_lamports_per_signature: target_lamports_per_signature.to_string(),
+                target_signatures_per_slot,
+                min_lamports_per_signature: min_lamports_per_signature.to_string(),
+                max_lamports_per_signature: max_lamports_per_signature.to_string(),
+                burn_percent,
+            },
+            rent: RentJson {
+                lamports_per_byte_year: genesis_config.rent.lamports_per_byte_year.to_string(),
+                exemption_threshold: genesis_config.rent.exemption_threshold,
+                burn_percent: genesis_config.rent.burn_percent,
+            },
+            inflation: genesis_config.inflation,
+            epoch_schedule: genesis_config.epoch_schedule.clone(),
+            cluster_type: genesis_config.cluster_type,
+        }
+    }
+}
+
+impl TryFrom<GenesisConfigJson> for GenesisConfig {
+    type Error = String;
+
+    fn try_from(json: GenesisConfigJson) -> Result<Self, Self::Error> {
+        let accounts = |accounts: BTreeMap<String, AccountJson>| {
+            accounts
+                .into_iter()
+                .map(|(pubkey, account)| {
+                    let account = Account::try_from(account)
+                        .map_err(|err| format!("account {pubkey}: {err}"))?;
+                    Ok((parse_pubkey(&pubkey)?, account))
+                })
+                .collect::<Result<BTreeMap<_, _>, String>>()
+        };
+        let fee_rate_governor = FeeRateGovernor {
+            lamports_per_signature: parse_lamports(&json.fee_rate_governor.lamports_per_signature)?,
+            target_lamports_per_signature: parse_lamports(
+                &json.fee_rate_governor.target_lamports_per_signature,
+            )?,
+            target_signatures_per_slot: json.fee_rate_governor.target_signatures_per_slot,
+            min_lamports_per_signature: parse_lamports(
+                &json.fee_rate_governor.min_lamports_per_signature,
+            )?,
+            max_lamports_per_signature: parse_lamports(
+                &json.fee_rate_governor.max_lamports_per_signature,
+            )?,
+            burn_percent: json.fee_rate_governor.burn_percent,
+        };
+        Ok(GenesisConfig {
+            creation_time: json.creation_time,
+            accounts: accounts(json.accounts)?,
+            native_instruction_processors: json
+                .native_instruction_processors
+                .into_iter()
+                .map(|(name, program_id)| Ok((name, parse_pubkey(&program_id)?)))
+                .collect::<Result<_, String>>()?,
+            rewards_pools: accounts(json.rewards_pools)?,
+            ticks_per_slot: json.ticks_per_slot,
+            poh_config: json.poh_config,
+            fee_rate_governor,
+            rent: Rent {
+                lamports_per_byte_year: parse_lamports(&json.rent.lamports_per_byte_year)?,
+                exemption_threshold: json.rent.exemption_threshold,
+                burn_percent: json.rent.burn_percent,
+            },
+            inflation: json.inflation,
+            epoch_schedule: json.epoch_schedule,
+            cluster_type: json.cluster_type,
+            ..GenesisConfig::default()
+        })
+    }
+}
+
+impl From<&Account> for AccountJson {
+    fn from(account: &Account) -> Self {
+        Self {
+            lamports: account.lamports.to_string(),
+            data: BASE64_STANDARD.encode(&account.data),
+            owner: account.owner.to_string(),
+            executable: account.executable,
+            rent_epoch: account.rent_epoch.to_string(),
+        }
+    }
+}
+
+impl TryFrom<AccountJson> for Account {
+    type Error = String;
+
+    fn try_from(json: AccountJson) -> Result<Self, Self::Error> {
+        Ok(Account {
+            lamports: parse_lamports(&json.lamports)?,
+            data: BASE64_STANDARD
+                .decode(&json.data)
+                .map_err(|err| format!("invalid base64 data: {err}"))?,
+            owner: parse_pubkey(&json.owner)?,
+            executable: json.executable,
+            rent_epoch: json
+                .rent_epoch
+                .parse()
+                .map_err(|err| format!("invalid rent epoch '{}': {err}", json.rent_epoch))?,
+        })
+    }
+}
+
+fn parse_pubkey(pubkey: &str) -> Result<Pubkey, String> {
+    pubkey
+        .parse()
+        .map_err(|err| format!("invalid pubkey '{pubkey}': {err}"))
+}
+
+fn parse_lamports(lamports: &str) -> Result<u64, String> {
+    lamports
+        .parse()
+        .map_err(|err| format!("invalid lamports '{lamports}': {err}"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// A genesis config with an account whose balance and data exercise the string and base64
+    /// encodings.
+    fn genesis_config() -> GenesisConfig {
+        let mut genesis_config = GenesisConfig {
+            creation_time: 1_704_067_200,
+            ..GenesisConfig::default()
+        };
+        genesis_config.add_account(
+            Pubkey::new_unique(),
+            Account {
+                lamports: u64::MAX,
+                data: vec![0, 1, 2, 255],
+                owner: Pubkey::new_unique(),
+                executable: true,
+                rent_epoch: u64::MAX,
+            }
+            .into(),
+        );
+        genesis_config
+            .native_instruction_processors
+            .push(("program".to_string(), Pubkey::new_unique()));
+        genesis_config
+    }
+
+    #[test]
+    fn test_json_round_trip() {
+        let genesis_config = genesis_config();
+        let json = serde_json::to_string(&GenesisConfigJson::from(&genesis_config)).unwrap();
+        assert!(
+            json.contains(&format!("\"lamports\":\"{}\"", u64::MAX)),
+            "{json}"
+        );
+        let json = serde_json::from_str::<GenesisConfigJson>(&json).unwrap();
+        assert_eq!(
+            GenesisConfig::try_from(json).unwrap().hash(),
+            genesis_config.hash()
+        );
+    }
+
+    #[test]
+    fn test_toml_round_trip() {
+        let genesis_config = genesis_config();
+        let toml = toml::to_string_pretty(&GenesisConfigJson::from(&genesis_config)).unwrap();
+        let json = toml::from_str::<GenesisConfigJson>(&toml).unwrap();
+        assert_eq!(
+            GenesisConfig::try_from(json).unwrap().hash(),
+            genesis_config.hash()
+        );
+    }
+
+    #[test]
+    fn test_invalid_account() {
+        let account = || AccountJson::from(&Account::default());
+        assert_eq!(
+            Account::try_from(AccountJson {
+                lamports: "-1".to_string(),
+                ..account()
+            }),
+            Err("invalid lamports '-1': invalid digit found in string".to_string())
+        );
+        assert!(
+            Account::try_from(AccountJson {
+                owner: "owner".to_string(),
+                ..account()
+            })
+            .unwrap_err()
+            .starts_with("invalid pubkey 'owner': ")
+        );
+        assert!(
+            Account::try_from(AccountJson {
+                data: "!".to_string(),
+                ..account()
+            })
+            .unwrap_err()
+            .starts_with("invalid base64 data: ")
+        );
+    }
+}
@@ -1,34 +1,86 @@
-use clap::{Arg, ArgAction, Command, crate_description, crate_name, crate_version};
-use solana_account::AccountSharedData;
+mod json;
+
+use crate::json::GenesisConfigJson;
+use base64::Engine;
+use base64::prelude::BASE64_STANDARD;
+use clap::{Arg, ArgAction, ArgMatches, Command, crate_description, crate_name, crate_version};
+use clap_complete::{Shell, generate};
+use serde::{Deserialize, Deserializer};
+use solana_account::{Account, AccountSharedData, ReadableAccount};
 use solana_accounts_db::hardened_unpack::MAX_GENESIS_ARCHIVE_UNPACKED_SIZE;
 use solana_clock as clock;
 use solana_clock::{Slot, UnixTimestamp};
 use solana_cluster_type::ClusterType;
 use solana_entry::poh::compute_hashes_per_tick;
-use solana_epoch_schedule::EpochSchedule;
+use solana_epoch_schedule::{EpochSchedule, MINIMUM_SLOTS_PER_EPOCH};
+use solana_feature_gate_interface::{self as feature, Feature};
 use solana_fee_calculator::FeeRateGovernor;
-use solana_genesis_config::GenesisConfig;
+use solana_genesis_config::{DEFAULT_GENESIS_FILE, GenesisConfig};
+use solana_hash::Hash;
 use solana_inflation::Inflation;
 use solana_ledger::blockstore::create_new_ledger;
-use solana_ledger::blockstore_options::LedgerColumnOptions;
+use solana_ledger::blockstore_options::{BlockstoreCompressionType, LedgerColumnOptions};
+use solana_loader_v3_interface::state::UpgradeableLoaderState;
 use solana_native_token::LAMPORTS_PER_SOL;
 use solana_poh_config::PohConfig;
 use solana_pubkey::Pubkey;
 use solana_rent::Rent;
-use solana_sdk_ids::system_program;
+use solana_runtime::genesis_utils;
+use solana_sdk_ids::{bpf_loader_upgradeable, stake, system_program};
+use solana_sha256_hasher::hash;
+use solana_shred_version::compute_shred_version;
 use solana_stake_interface::state::StakeStateV2;
 use solana_stake_program::{add_genesis_accounts, stake_state};
+use solana_system_interface::MAX_PERMITTED_DATA_LENGTH;
 use solana_vote_interface::state::VoteStateV3;
 use solana_vote_program::vote_state;
 use solarium_clap_utils::{
-    parse_percentage, parse_pubkey, parse_slot, unix_timestamp_from_rfc3339_datetime,
+    parse_duration, parse_genesis_hash, parse_percentage, parse_pubkey, parse_slot, parse_triples,
+    unix_timestamp_from_rfc3339_datetime,
 };
-use std::path::PathBuf;
-use std::slice::Iter;
+use std::cmp::Reverse;
+use std::collections::{BTreeMap, HashSet};
+use std::fmt::Display;
+use std::fs;
+use std::path::{Path, PathBuf};
 use std::time::Duration;
 use std::{io, process};
 
-fn main() -> Result<(), Box<dyn std::error::Error>> {
+/// Address of the --reserve-lamports account. Nobody holds its private key, so the
+/// lamports it holds cannot circulate.
+const RESERVE_PUBKEY: Pubkey =
+    Pubkey::from_str_const("GenesisReserve11111111111111111111111111111");
+
+/// The programs --spl-programs bakes in, as program id, ELF file name in the directory and
+/// account table label.
+const SPL_PROGRAMS: [(Pubkey, &str, &str); 3] = [
+    (
+        Pubkey::from_str_const("TokenkegQfeZyiNwAJbNbGKPFXCWuBvf9Ss623VQ5DA"),
+        "spl_token.so",
+        "spl-token",
+    ),
+    (
+        Pubkey::from_str_const("TokenzQdBNbLqP5VEhdkAS6EPFLC1PHnBqCXEpPxuEb"),
+        "spl_token_2022.so",
+        "spl-token-2022",
+    ),
+    (
+        Pubkey::from_str_const("ATokenGPvbdGVxr1b2hvZbsiqW5xWH25efTNsLJA8knL"),
+        "spl_associated_token_account.so",
+        "spl-associated-token-account",
+    ),
+];
+
+/// Default for --min-ticks-per-epoch: the shortest epoch the epoch schedule allows, at the
+/// default tick rate.
+const DEFAULT_MIN_TICKS_PER_EPOCH: u64 = MINIMUM_SLOTS_PER_EPOCH * clock::DEFAULT_TICKS_PER_SLOT;
+
+/// Room the genesis archive needs on top of the serialized genesis config for the rocksdb
+/// ledger holding the genesis ticks. Its size is only known once the ledger is written, and
+/// a fresh ledger of a few slots stays well within this.
+const GENESIS_ARCHIVE_LEDGER_MARGIN: u64 = 4 * 1024 * 1024;
+
+fn app() -> Command {
     let default_faucet_pubkey = solana_cli_config::Config::default().keypair_path;
     let (
         default_target_lamports_per_signature,
@@ -65,12 +117,12 @@ fn main() -> Result<(), Box<dyn std::error::Error>> {
         .max(rent.minimum_balance(StakeStateV2::size_of()))
         .to_string();
 
-    let default_target_tick_duration = PohConfig::default().target_tick_duration;
     let default_ticks_per_slot = clock::DEFAULT_TICKS_PER_SLOT.to_string();
+    let default_min_ticks_per_epoch = DEFAULT_MIN_TICKS_PER_EPOCH.to_string();
     let default_cluster_type = "mainnet-beta";
     let default_genesis_archive_unpacked_size = MAX_GENESIS_ARCHIVE_UNPACKED_SIZE.to_string();
 
-    let matches = Command::new(crate_name!())
+    Command::new(crate_name!())
         .about(crate_description!())
         .version(crate_version!())
         .arg(
@@ -80,25 +132,114 @@ fn main() -> Result<(), Box<dyn std::error::Error>> {
                 .value_parser(unix_timestamp_from_rfc3339_datetime)
                 .help("Time when the bootstrap validator will start the cluster [default: current system time]"),
         )
+        .arg(
+            Arg::new("reproducible")
+                .long("reproducible")
+                .action(ArgAction::SetTrue)
+                .requires("creation_time")
+                .help(
+                    "Guarantee the same inputs always give the same genesis hash. Requires \
+                     --creation-time, as the hash would otherwise include the current time, \
+                     and a number or sleep for --hashes-per-tick, as auto may measure this \
+                     computer. The --primordial-accounts-file entries are sorted by pubkey \
+                     before they are added and a pubkey listed more than once is rejected, so \
+                     neither the order of the files nor of their entries affects the hash. \
+                     This may change the hash compared to adding them in file order",
+                ),
+        )
         .arg(
             Arg::new("bootstrap_validator")
                 .short('b')
                 .long("bootstrap-validator")
                 .value_name("IDENTITY_PUBKEY VOTE_PUBKEY STAKE_PUBKEY")
-                .value_parser(parse_pubkey)
                 .number_of_values(3)
                 .action(ArgAction::Append)
-                .required(true)
+                .required_unless_present("bootstrap_validators_file")
                 .help("The bootstrap validator's identity, vote and stake pubkeys"),
         )
+        .arg(
+            Arg::new("bootstrap_validators_file")
+                .long("bootstrap-validators-file")
+                .value_name("PATH")
+                .help(
+                    "File of bootstrap validators, one IDENTITY_PUBKEY VOTE_PUBKEY STAKE_PUBKEY \
+                     triple per line, separated by commas as in a CSV file or by whitespace. \
+                     Merged with any --bootstrap-validator, a validator listed in both is \
+                     added once. Blank lines and lines starting with # are ignored",
+                ),
+        )
         .arg(
             Arg::new("ledger_path")
                 .short('l')
                 .long("ledger")
                 .value_name("DIR")
-                .required(true)
+                .required_unless_present_any(["dry_run", "skip_ledger_write"])
                 .help("Use directory as persistent ledger location"),
         )
+        .arg(
+            Arg::new("show_timing")
+                .long("show-timing")
+                .action(ArgAction::SetTrue)
+                .help(
+                    "Print the tick, slot and epoch rates derived from the PoH and epoch \
+                     settings",
+                ),
+        )
+        .arg(
+            Arg::new("expected_genesis_hash")
+                .long("expected-genesis-hash")
+                .value_name("HASH")
+                .value_parser(parse_genesis_hash)
+                .help("Fail if the genesis hash of the created ledger is not HASH"),
+        )
+        .arg(
+            Arg::new("verbose")
+                .short('v')
+                .long("verbose")
+                .action(ArgAction::SetTrue)
+                .help(
+                    "Print every genesis account with its balance, rent exempt minimum, owner, \
+                     data length and executable flag",
+                ),
+        )
+        .arg(
+            Arg::new("canonical_dump")
+                .long("canonical-dump")
+                .value_name("PATH")
+                .help(
+                    "Write the genesis parameters to PATH as sorted \"key = value\" lines, \
+                     so that the dumps of two configs diff cleanly",
+                ),
+        )
+        .arg(
+            Arg::new("json_output")
+                .long("json-output")
+                .value_name("PATH")
+                .help(
+                    "Also write the genesis config, in the JSON form of `export`, with its hash \
+                     and shred version to PATH. The human readable output is still printed",
+                ),
+        )
+        .arg(
+            Arg::new("dry_run")
+                .long("dry-run")
+                .action(ArgAction::SetTrue)
+                .help(
+                    "Assemble and print the genesis config without creating the ledger. \
+                     --ledger is not required in this mode",
+                ),
+        )
+        .arg(
+            Arg::new("skip_ledger_write")
+                .long("skip-ledger-write")
+                .action(ArgAction::SetTrue)
+                .conflicts_with_all(["dry_run", "ledger_path"])
+                .help(
+                    "Run every check and print the genesis config along with its genesis hash \
+                     and shred version, checked against --expected-genesis-hash, computed in \
+                     memory without creating the ledger",
+                ),
+        )
         .arg(
             Arg::new("faucet_lamports")
                 .short('t')
@@ -117,6 +258,16 @@ fn main() -> Result<(), Box<dyn std::error::Error>> {
                 .default_value(default_faucet_pubkey)
                 .help("Path to file containing the faucet's pubkey"),
         )
+        .arg(
+            Arg::new("reserve_lamports")
+                .long("reserve-lamports")
+                .value_name("LAMPORTS")
+                .value_parser(clap::value_parser!(u64))
+                .help(format!(
+                    "Hold LAMPORTS in a system owned reserve account at {RESERVE_PUBKEY}, which \
+                     counts towards capitalization but has no private key to spend it"
+                )),
+        )
         .arg(
             Arg::new("bootstrap_stake_authorized_pubkey")
                 .long("bootstrap-stake-authorized-pubkey")
@@ -143,6 +294,17 @@ fn main() -> Result<(), Box<dyn std::error::Error>> {
                 .value_parser(clap::value_parser!(u64))
                 .help("Number of lamports to assign to the bootstrap validator's stake account"),
         )
+        .arg(
+            Arg::new("max_faucet_and_validator_lamports")
+                .long("max-faucet-and-validator-lamports")
+                .value_name("LAMPORTS")
+                .value_parser(clap::value_parser!(u64))
+                .help(
+                    "Fail if --faucet-lamports plus the --bootstrap-validator-lamports and \
+                     --bootstrap-validator-stake-lamports of every bootstrap validator exceed \
+                     LAMPORTS",
+                ),
+        )
         .arg(
             Arg::new("target_lamports_per_signature")
                 .long("target-lamports-per-signature")
@@ -192,6 +354,20 @@ fn main() -> Result<(), Box<dyn std::error::Error>> {
                 .value_parser(parse_percentage)
                 .help("percentage of collected fee to burn"),
         )
+        .arg(
+            Arg::new("burn_all")
+                .long("burn-all")
+                .action(ArgAction::SetTrue)
+                .conflicts_with_all(["rent_burn_percentage", "fee_burn_percentage"])
+                .help("Burn all collected rent and fees, same as --rent-burn-percentage 100 --fee-burn-percentage 100"),
+        )
+        .arg(
+            Arg::new("burn_none")
+                .long("burn-none")
+                .action(ArgAction::SetTrue)
+                .conflicts_with_all(["rent_burn_percentage", "fee_burn_percentage", "burn_all"])
+                .help("Burn none of the collected rent and fees, same as --rent-burn-percentage 0 --fee-burn-percentage 0"),
+        )
         .arg(
             Arg::new("vote_commission_percentage")
                 .long("vote-commission-percentage")
@@ -216,9 +392,12 @@ fn main() -> Result<(), Box<dyn std::error::Error>> {
         .arg(
             Arg::new("target_tick_duration")
                 .long("target-tick-duration")
-                .value_name("MILLIS")
-                .value_parser(clap::value_parser!(u64))
-                .help("The target tick rate of the cluster in milliseconds"),
+                .value_name("DURATION")
+                .value_parser(parse_duration)
+                .help(
+                    "The target tick duration of the cluster, such as 6.25ms, with an ms, s, m, \
+                     h or d suffix. A bare number is in milliseconds",
+                ),
         )
         .arg(
             Arg::new("hashes_per_tick")
@@ -229,7 +408,8 @@ fn main() -> Result<(), Box<dyn std::error::Error>> {
                     "How many PoH hashes to roll before emitting the next tick. \
                      If \"auto\", determine based on --target-tick-duration \
                      and the hash rate of this computer. If \"sleep\", for development \
-                     sleep for --target-tick-duration instead of hashing",
+                     sleep for --target-tick-duration instead of hashing, which other \
+                     --cluster-type values reject",
                 ),
         )
         .arg(
@@ -253,7 +433,36 @@ fn main() -> Result<(), Box<dyn std::error::Error>> {
                 .action(ArgAction::SetTrue)
                 .help(
                     "When enabled epochs start short and will grow. \
-                     Useful for warming up stake quickly during development",
+                     Useful for warming up stake quickly during development. \
+                     On by default for --cluster-type development",
+                ),
+        )
+        .arg(
+            Arg::new("disable_warmup_epochs")
+                .long("disable-warmup-epochs")
+                .action(ArgAction::SetTrue)
+                .conflicts_with("enable_warmup_epochs")
+                .help("Use full length epochs from the start, even for --cluster-type development"),
+        )
+        .arg(
+            Arg::new("min_ticks_per_epoch")
+                .long("min-ticks-per-epoch")
+                .value_name("TICKS")
+                .default_value(default_min_ticks_per_epoch)
+                .value_parser(clap::value_parser!(u64))
+                .help(
+                    "Refuse to create a genesis config whose epochs, --ticks-per-slot times \
+                     --slots-per-epoch, have fewer ticks than this",
+                ),
+        )
+        .arg(
+            Arg::new("stake_deactivating")
+                .long("stake-deactivating")
+                .action(ArgAction::SetTrue)
+                .help(
+                    "Bake the bootstrap validator stake accounts in as delegated but already \
+                     deactivating at epoch 0, so stake cooldown can be observed from the start. \
+                     Without it the bootstrap stake is fully active from genesis",
                 ),
         )
         .arg(
@@ -261,15 +470,52 @@ fn main() -> Result<(), Box<dyn std::error::Error>> {
                 .long("primordial-accounts-file")
                 .value_name("FILENAME")
                 .action(ArgAction::Append)
-                .help("The location of pubkey for primordial accounts and balance"),
+                .help(
+                    "The location of pubkey for primordial accounts and balance. Either a YAML \
+                     map of pubkeys to accounts with a balance and optionally an owner, base64 \
+                     data and executable flag, or, with a .csv extension, rows of \
+                     pubkey,balance[,owner] with the owner defaulting to the system program",
+                ),
         )
         .arg(
             Arg::new("validator_accounts_file")
                 .long("validator-accounts-file")
                 .value_name("FILENAME")
                 .action(ArgAction::Append)
-                .help("The location of a file containing a list of identity, vote, and \
-                stake pubkeys and balances for validator accounts to bake into genesis")
+                .help("The location of a YAML file containing a list of validator_accounts, each \
+                with an identity_account, vote_account and stake_account (pubkeys or keypair paths), \
+                balance_lamports, stake_lamports and an optional commission overriding \
+                --vote-commission-percentage, to bake into genesis")
+        )
+        .arg(
+            Arg::new("accounts_schema")
+                .long("accounts-schema")
+                .value_name("FILENAME")
+                .help(
+                    "JSON file constraining the accounts loaded from --primordial-accounts-file \
+                     and --validator-accounts-file: their allowed_owners and max_data_len, and \
+                     the required_fields every --validator-accounts-file entry must set. All \
+                     violations are reported at once, before any account is added",
+                ),
+        )
+        .arg(
+            Arg::new("spl_programs")
+                .long("spl-programs")
+                .value_name("DIR")
+                .help(
+                    "Bake the SPL Token, Token-2022 and Associated Token Account programs into \
+                     genesis as non-upgradeable programs of the upgradeable BPF loader, from \
+                     spl_token.so, spl_token_2022.so and spl_associated_token_account.so in DIR",
+                ),
+        )
+        .arg(
+            Arg::new("enforce_rent_exemption")
+                .long("enforce-rent-exemption")
+                .action(ArgAction::SetTrue)
+                .help(
+                    "Fail if any genesis account holds less than the rent exempt minimum for \
+                     its data length. The bootstrap validator accounts are not checked",
+                ),
         )
         .arg(
             Arg::new("cluster_type")
@@ -292,16 +538,194 @@ fn main() -> Result<(), Box<dyn std::error::Error>> {
                 .value_parser(["pico", "full", "none"])
                 .help("Selects inflation"),
         )
+        .arg(
+            Arg::new("activate_all_features")
+                .long("activate-all-features")
+                .visible_alias("enable-all-features")
+                .action(ArgAction::SetTrue)
+                .help(
+                    "Activate every known feature at slot 0, the default for --cluster-type \
+                     development. Not allowed with --cluster-type mainnet-beta or testnet",
+                ),
+        )
+        .arg(
+            Arg::new("deactivate_all_features")
+                .long("deactivate-all-features")
+                .action(ArgAction::SetTrue)
+                .conflicts_with("activate_all_features")
+                .help(
+                    "Leave every feature inactive at genesis, the default for every cluster \
+                     type but development",
+                ),
+        )
+        .arg(
+            Arg::new("activate_feature")
+                .long("activate-feature")
+                .value_name("PUBKEY:SLOT")
+                .action(ArgAction::Append)
+                .value_parser(parse_feature_activation)
+                .help(
+                    "Add the account of the feature PUBKEY, or of the feature keypair file, \
+                     activated at SLOT rather than at genesis. May be given several times, and \
+                     overrides --activate-all-features for that feature",
+                ),
+        )
+        .arg(
+            Arg::new("rocksdb_compression")
+                .long("rocksdb-compression")
+                .value_name("COMPRESSION_TYPE")
+                .value_parser(["none", "snappy", "lz4", "zlib"])
+                .default_value("none")
+                .help("Compression applied to the eligible ledger columns"),
+        )
+        .arg(
+            Arg::new("completions")
+                .long("completions")
+                .value_name("SHELL")
+                .value_parser(clap::value_parser!(Shell))
+                .exclusive(true)
+                .hide(true)
+                .help("Print the completion script for SHELL to stdout and exit"),
+        )
+        .subcommands(subcommands())
+        .args_conflicts_with_subcommands(true)
+        .subcommand_negates_reqs(true)
+}
+
+fn subcommands() -> [Command; 4] {
+    [
+        Command::new("export")
+            .about("Write the genesis config of an existing ledger as JSON or TOML")
+            .arg(
+                Arg::new("ledger_path")
+                    .short('l')
+                    .long("ledger")
+                    .value_name("DIR")
+                    .required(true)
+                    .help("Ledger directory to read the genesis config from"),
+            )
+            .arg(
+                Arg::new("output")
+                    .short('o')
+                    .long("output")
+                    .value_name("FILE")
+                    .help("Write the genesis config to FILE [default: stdout]"),
+            )
+            .arg(
+                Arg::new("output_format")
+                    .long("output-format")
+                    .value_name("FORMAT")
+                    .value_parser(["json", "toml"])
+                    .default_value("json")
+                    .help("Format of the written genesis config, import reads either"),
+            ),
+        Command::new("import")
+            .about("Create a new ledger from a genesis config written by export")
+            .arg(
+                Arg::new("input")
+                    .short('i')
+                    .long("input")
+                    .value_name("FILE")
+                    .required(true)
+                    .help(
+                        "Genesis config to create the ledger from, read as TOML when its \
+                         extension is .toml and as JSON otherwise",
+                    ),
+            )
+            .arg(
+                Arg::new("ledger_path")
+                    .short('l')
+                    .long("ledger")
+                    .value_name("DIR")
+                    .required(true)
+                    .help("Use directory as persistent ledger location"),
+            ),
+        Command::new("verify")
+            .about(
+                "Load the genesis config of an existing ledger and print its summary and hash, \
+                 without changing anything",
+            )
+            .arg(
+                Arg::new("ledger_path")
+                    .short('l')
+                    .long("ledger")
+                    .value_name("DIR")
+                    .required(true)
+                    .help("Ledger directory to read the genesis config from"),
+            ),
+        Command::new("print-defaults")
+            .alias("print-default-genesis-config")
+            .about(
+                "Print the genesis config made when every optional argument is left out, and \
+                 where each of its values comes from",
+            )
+            .arg(
+                Arg::new("cluster_type")
+                    .long("cluster-type")
+                    .value_parser(clap::value_parser!(ClusterType))
+                    .default_value("mainnet-beta")
+                    .help("Cluster type whose defaults to print"),
+            ),
+    ]
+}
+
+fn main() -> Result<(), Box<dyn std::error::Error>> {
+    let default_target_tick_duration = PohConfig::default().target_tick_duration;
+
+    // Completions need none of the other arguments, whose defaults may not even parse here.
+    if let Some(shell) = app()
+        .ignore_errors(true)
         .try_get_matches()
-        .unwrap_or_else(|e| {
-            eprintln!("failed to parse args: {}", e);
-            e.exit()
-        });
+        .ok()
+        .and_then(|matches| matches.get_one::<Shell>("completions").copied())
+    {
+        let mut command = app();
+        let name = command.get_name().to_string();
+        generate(shell, &mut command, name, &mut io::stdout());
+        return Ok(());
+    }
+    // Likewise the subcommands, so they are parsed on their own.
+    if app()
+        .ignore_errors(true)
+        .try_get_matches()
+        .is_ok_and(|matches| matches.subcommand_name().is_some())
+    {
+        let matches = Command::new(crate_name!())
+            .subcommands(subcommands())
+            .subcommand_required(true)
+            .get_matches();
+        return match matches.subcommand() {
+            Some(("export", matches)) => export_genesis(matches),
+            Some(("import", matches)) => import_genesis(matches),
+            Some(("verify", matches)) => verify_genesis(matches),
+            Some(("print-defaults", matches)) => print_defaults(matches),
+            _ => unreachable!(),
+        };
+    }
+
+    let matches = app().try_get_matches().unwrap_or_else(|e| {
+        eprintln!("failed to parse args: {}", e);
+        e.exit()
+    });
 
-    let ledger_path = PathBuf::from(matches.try_get_one::<String>("ledger_path")?.unwrap());
+    let ledger_path = matches
+        .try_get_one::<String>("ledger_path")?
+        .map(PathBuf::from);
+    let dry_run = matches.get_flag("dry_run");
+    let reproducible = matches.get_flag("reproducible");
 
     // This part of the code is responsible for the "Rent" section of the output.
     // It reads the command-line arguments for rent configuration and creates a Rent struct.
+    // --burn-all/--burn-none set both burn percentages at once, clap rejects them together
+    // with the per-category flags.
+    let burn_percent_override = if matches.get_flag("burn_all") {
+        Some(100)
+    } else if matches.get_flag("burn_none") {
+        Some(0)
+    } else {
+        None
+    };
+
     let rent = Rent {
         lamports_per_byte_year: matches
             .try_get_one::<u64>("lamports_per_byte_year")?
@@ -311,27 +735,56 @@ fn main() -> Result<(), Box<dyn std::error::Error>> {
             .try_get_one::<f64>("rent_exemption_threshold")?
             .copied()
             .unwrap(),
-        burn_percent: matches
-            .try_get_one::<u8>("rent_burn_percentage")?
-            .copied()
-            .unwrap(),
+        burn_percent: burn_percent_override.unwrap_or(
+            matches
+                .try_get_one::<u8>("rent_burn_percentage")?
+                .copied()
+                .unwrap(),
+        ),
     };
 
-    // can use unwrap as the param is required.
-    let bootstrap_validator_pubkeys = matches
-        .try_get_many::<Pubkey>("bootstrap_validator")?
-        .unwrap()
-        .copied()
-        .collect::<Vec<_>>();
-    assert_eq!(bootstrap_validator_pubkeys.len() % 3, 0);
+    let mut bootstrap_validators = parse_triples(
+        &matches
+            .try_get_many::<String>("bootstrap_validator")?
+            .unwrap_or_default()
+            .cloned()
+            .collect::<Vec<_>>(),
+        parse_pubkey,
+    )
+    .and_then(|validators| {
+        validate_bootstrap_validators(&validators)?;
+        Ok(validators)
+    })
+    .unwrap_or_else(|err| {
+        eprintln!("Error: --bootstrap-validator: {err}");
+        process::exit(1);
+    });
+    if let Some(path) = matches.try_get_one::<String>("bootstrap_validators_file")? {
+        let validators = read_bootstrap_validators_file(path)
+            .and_then(|validators| {
+                validate_bootstrap_validators(&validators)?;
+                Ok(validators)
+            })
+            .unwrap_or_else(|err| {
+                eprintln!("Error: --bootstrap-validators-file {path}: {err}");
+                process::exit(1);
+            });
+        bootstrap_validators.extend(validators);
+        // A validator may be listed both with --bootstrap-validator and in the file.
+        let mut listed = HashSet::new();
+        bootstrap_validators.retain(|validator| listed.insert(*validator));
+    }
 
-    // Ensure there are no duplicated pubkeys in the --bootstrap-validator list
+    // Ensure no pubkey is shared between different bootstrap validators
     {
-        let mut v = bootstrap_validator_pubkeys.clone();
+        let mut v = bootstrap_validators.concat();
         v.sort();
         v.dedup();
-        if v.len() != bootstrap_validator_pubkeys.len() {
-            eprintln!("Error: --bootstrap-validator pubkeys cannot be duplicated");
+        if v.len() != bootstrap_validators.len() * 3 {
+            eprintln!(
+                "Error: --bootstrap-validator and --bootstrap-validators-file pubkeys cannot be \
+                 shared between validators"
+            );
             process::exit(1);
         }
     }
@@ -354,6 +807,20 @@ fn main() -> Result<(), Box<dyn std::error::Error>> {
         .copied()
         .unwrap_or(0);
     let faucet_pubkey = matches.try_get_one::<Pubkey>("faucet_pubkey")?.copied();
+    let reserve_lamports = matches.try_get_one::<u64>("reserve_lamports")?.copied();
+    check_faucet_and_validator_lamports(
+        faucet_lamports,
+        bootstrap_validators.len(),
+        bootstrap_validator_lamports,
+        bootstrap_validator_stake_lamports,
+        matches
+            .try_get_one::<u64>("max_faucet_and_validator_lamports")?
+            .copied(),
+    )
+    .unwrap_or_else(|err| {
+        eprintln!("Error: {err}");
+        process::exit(1);
+    });
 
     // This line is responsible for the "Ticks per slot" value in the output.
     // It reads the --ticks-per-slot command-line argument.
@@ -374,18 +841,20 @@ fn main() -> Result<(), Box<dyn std::error::Error>> {
             .copied()
             .unwrap(),
     );
-    fee_rate_governor.burn_percent = matches
-        .try_get_one::<u8>("fee_burn_percentage")?
-        .copied()
-        .unwrap();
+    fee_rate_governor.burn_percent = burn_percent_override.unwrap_or(
+        matches
+            .try_get_one::<u8>("fee_burn_percentage")?
+            .copied()
+            .unwrap(),
+    );
 
     // This part of the code is responsible for the "Target tick duration" value in the output.
     // It reads the --target-tick-duration command-line argument.
     let mut poh_config = PohConfig {
-        target_tick_duration: match matches.try_get_one::<u64>("target_tick_duration")? {
-            None => default_target_tick_duration,
-            Some(&tick) => Duration::from_micros(tick),
-        },
+        target_tick_duration: matches
+            .try_get_one::<Duration>("target_tick_duration")?
+            .copied()
+            .unwrap_or(default_target_tick_duration),
         ..PohConfig::default()
     };
 
@@ -396,6 +865,30 @@ fn main() -> Result<(), Box<dyn std::error::Error>> {
         .copied()
         .unwrap();
 
+    let activate_all_features = if matches.get_flag("activate_all_features") {
+        match cluster_type {
+            ClusterType::MainnetBeta | ClusterType::Testnet => {
+                eprintln!(
+                    "Error: --activate-all-features cannot be used with --cluster-type {}",
+                    cluster_type_name(cluster_type)
+                );
+                process::exit(1);
+            }
+            ClusterType::Devnet => {
+                eprintln!(
+                    "Warning: --activate-all-features activates features that devnet may not \
+                     have enabled yet"
+                );
+            }
+            ClusterType::Development => {}
+        }
+        true
+    } else if matches.get_flag("deactivate_all_features") {
+        false
+    } else {
+        default_activate_all_features(cluster_type)
+    };
+
     // Get the features to deactivate if provided
     // let features_to_deactivate = features_to_deactivate_for_cluster(&cluster_type, &matches)
     //     .unwrap_or_else(|e| {
@@ -405,50 +898,51 @@ fn main() -> Result<(), Box<dyn std::error::Error>> {
 
     // This match statement is responsible for the "Hashes per tick" value in the output.
     // It determines the number of hashes per tick based on the --hashes-per-tick argument and cluster type.
-    match matches
-        .try_get_one::<String>("hashes_per_tick")?
-        .unwrap()
-        .as_str()
-    {
-        "auto" => match cluster_type {
-            ClusterType::Development => {
-                let hashes_per_tick =
-                    compute_hashes_per_tick(poh_config.target_tick_duration, 1_000_000);
-                poh_config.hashes_per_tick = Some(hashes_per_tick / 2); // use 50% of peak ability
-            }
-            ClusterType::Devnet | ClusterType::Testnet | ClusterType::MainnetBeta => {
-                poh_config.hashes_per_tick = Some(clock::DEFAULT_HASHES_PER_TICK);
-            }
-        },
-        "sleep" => {
-            poh_config.hashes_per_tick = None;
-        }
-        s => {
-            poh_config.hashes_per_tick = Some(s.parse::<u64>().unwrap_or_else(|err| {
-                eprintln!("Error: invalid value for --hashes-per-tick: {s}: {err}");
-                process::exit(1);
-            }));
+    poh_config.hashes_per_tick = parse_hashes_per_tick(
+        matches.try_get_one::<String>("hashes_per_tick")?.unwrap(),
+        cluster_type,
+        poh_config.target_tick_duration,
+        reproducible,
+    )
+    .unwrap_or_else(|err| {
+        eprintln!("Error: {err}");
+        process::exit(1);
+    });
+    match check_hashes_per_tick(poh_config.hashes_per_tick, cluster_type) {
+        Ok(Some(warning)) => eprintln!("Warning [{}]: {}", warning.code, warning.message),
+        Ok(None) => {}
+        Err(err) => {
+            eprintln!("Error: {err}");
+            process::exit(1);
         }
     }
 
     // This part of the code is responsible for the "Slots per epoch" value in the output.
     // It determines the number of slots per epoch based on the --slots-per-epoch argument and cluster type.
     let slots_per_epoch = match matches.try_get_one::<Slot>("slots_per_epoch")? {
-        None => match cluster_type {
-            ClusterType::Development => clock::DEFAULT_DEV_SLOTS_PER_EPOCH,
-            ClusterType::Devnet | ClusterType::Testnet | ClusterType::MainnetBeta => {
-                clock::DEFAULT_SLOTS_PER_EPOCH
-            }
-        },
+        None => default_slots_per_epoch(cluster_type),
         Some(slot) => *slot,
     };
     // This part of the code is responsible for the "Warmup epochs" value in the output.
-    // It enables or disables warmup epochs based on the --enable-warmup-epochs flag.
-    let epoch_schedule = EpochSchedule::custom(
-        slots_per_epoch,
-        slots_per_epoch,
-        matches.get_flag("enable_warmup_epochs"),
+    // Development clusters warm up by default, --enable/--disable-warmup-epochs override that.
+    let warmup_epochs = if matches.get_flag("enable_warmup_epochs") {
+        true
+    } else if matches.get_flag("disable_warmup_epochs") {
+        false
+    } else {
+        default_warmup_epochs(cluster_type)
+    };
+    let min_ticks_per_epoch = matches
+        .try_get_one::<u64>("min_ticks_per_epoch")?
+        .copied()
+        .unwrap();
+    validate_ticks_per_epoch(ticks_per_slot, slots_per_epoch, min_ticks_per_epoch).unwrap_or_else(
+        |err| {
+            eprintln!("Error: {err}");
+            process::exit(1);
+        },
     );
+    let epoch_schedule = EpochSchedule::custom(slots_per_epoch, slots_per_epoch, warmup_epochs);
 
     let mut genesis_config = GenesisConfig {
         // This field corresponds to the "Native instruction processors" in the output.
@@ -482,13 +976,20 @@ fn main() -> Result<(), Box<dyn std::error::Error>> {
 
     add_validator_accounts(
         &mut genesis_config,
-        &mut bootstrap_validator_pubkeys.iter(),
+        &bootstrap_validators,
         bootstrap_validator_lamports,
         bootstrap_validator_stake_lamports,
         commission,
         &rent,
         bootstrap_stake_authorized_pubkey.as_ref(),
     )?;
+    if matches.get_flag("stake_deactivating") {
+        for [_, _, stake_pubkey] in &bootstrap_validators {
+            // can use unwrap as add_validator_accounts just created the account.
+            let stake_account = genesis_config.accounts.get_mut(stake_pubkey).unwrap();
+            deactivate_stake_at_genesis(stake_account)?;
+        }
+    }
 
     // This block is responsible for the "Creation time" in the output.
     // It sets the creation_time field in the GenesisConfig.
@@ -499,15 +1000,41 @@ fn main() -> Result<(), Box<dyn std::error::Error>> {
         genesis_config.creation_time = creation_time;
     }
 
+    if faucet_lamports > 0 {
+        match check_faucet_lamports(faucet_lamports, &rent, cluster_type) {
+            Ok(Some(warning)) => eprintln!("Warning [{}]: {}", warning.code, warning.message),
+            Ok(None) => {}
+            Err(err) => {
+                eprintln!("Error: {err}");
+                process::exit(1);
+            }
+        }
+    }
     if let Some(faucet_pubkey) = faucet_pubkey {
         genesis_config.add_account(
             faucet_pubkey,
             AccountSharedData::new(faucet_lamports, 0, &system_program::id()),
         );
     }
+    if let Some(reserve_lamports) = reserve_lamports {
+        genesis_config.add_account(
+            RESERVE_PUBKEY,
+            AccountSharedData::new(reserve_lamports, 0, &system_program::id()),
+        );
+        println!("Reserve account: {RESERVE_PUBKEY} with {reserve_lamports} lamports");
+    }
 
     add_genesis_accounts(&mut genesis_config);
-    // genesis_utils::activate_all_features(&mut genesis_config);
+    if activate_all_features {
+        genesis_utils::activate_all_features(&mut genesis_config);
+    }
+    if let Some(activations) = matches.try_get_many::<(Pubkey, Slot)>("activate_feature")? {
+        let activations = activations.copied().collect::<Vec<_>>();
+        add_feature_accounts(&mut genesis_config, &activations).unwrap_or_else(|err| {
+            eprintln!("Error: --activate-feature: {err}");
+            process::exit(1);
+        });
+    }
     // if !features_to_deactivate.is_empty() {
     //     solana_runtime::genesis_utils::deactivate_features(
     //         &mut genesis_config,
@@ -515,17 +1042,60 @@ fn main() -> Result<(), Box<dyn std::error::Error>> {
     //     );
     // }
 
-    // if let Some(files) = matches.try_get_many::<&str>("primordial_accounts_file")? {
-    //     for file in files {
-    //         load_genesis_accounts(file, &mut genesis_config)?;
-    //     }
-    // }
-    //
-    // if let Some(files) = matches.try_get_many::<&str>("validator_accounts_file") {
-    //     for file in files {
-    //         load_validator_accounts(file, commission, &rent, &mut genesis_config)?;
-    //     }
-    // }
+    let accounts_schema = matches
+        .try_get_one::<String>("accounts_schema")?
+        .map(|file| load_accounts_schema(file))
+        .transpose()?;
+    let mut violations = vec![];
+    let mut primordial_accounts = vec![];
+    for file in matches
+        .try_get_many::<String>("primordial_accounts_file")?
+        .into_iter()
+        .flatten()
+    {
+        let (accounts, lamports) = read_genesis_accounts(file)?;
+        println!("Primordial accounts: {lamports} lamports from {file}");
+        if let Some(schema) = &accounts_schema {
+            for (pubkey, account) in &accounts {
+                violations.extend(account_schema_violations(schema, file, pubkey, account));
+            }
+        }
+        primordial_accounts.extend(accounts);
+    }
+    if reproducible {
+        sort_genesis_accounts(&mut primordial_accounts).unwrap_or_else(|err| {
+            eprintln!("Error: --reproducible: {err}");
+            process::exit(1);
+        });
+    }
+    let mut validator_accounts = vec![];
+    for file in matches
+        .try_get_many::<String>("validator_accounts_file")?
+        .into_iter()
+        .flatten()
+    {
+        let (accounts, file_violations) =
+            read_validator_accounts(file, commission, &rent, accounts_schema.as_ref())?;
+        validator_accounts.extend(accounts);
+        violations.extend(file_violations);
+    }
+    if !violations.is_empty() {
+        eprintln!("Error: --accounts-schema is violated:");
+        for violation in violations {
+            eprintln!("  {violation}");
+        }
+        process::exit(1);
+    }
+    for (pubkey, account) in primordial_accounts.into_iter().chain(validator_accounts) {
+        genesis_config.add_account(pubkey, account);
+    }
+
+    if let Some(dir) = matches.try_get_one::<String>("spl_programs")? {
+        add_spl_programs(&mut genesis_config, Path::new(dir)).unwrap_or_else(|err| {
+            eprintln!("Error: --spl-programs {dir}: {err}");
+            process::exit(1);
+        });
+    }
 
     let max_genesis_archive_unpacked_size = matches
         .try_get_one::<u64>("max_genesis_archive_unpacked_size")?
@@ -533,13 +1103,69 @@ fn main() -> Result<(), Box<dyn std::error::Error>> {
         .unwrap();
 
     // This part of the code calculates the total lamports in all accounts, which is part of the "Capitalization" output.
-    let issued_lamports = genesis_config
-        .accounts
-        .values()
-        .map(|account| account.lamports)
-        .sum::<u64>();
+    let issued_lamports = issued_lamports(&genesis_config).unwrap_or_else(|err| {
+        eprintln!("Error: {err}");
+        process::exit(1);
+    });
     println!("Issued lamports: {issued_lamports}",);
 
+    validate_account_data_sizes(&genesis_config).unwrap_or_else(|err| {
+        eprintln!("Error: {err}");
+        process::exit(1);
+    });
+    if matches.get_flag("enforce_rent_exemption") {
+        // The bootstrap validator balances have their own checks, see add_validator_accounts.
+        let exempt = bootstrap_validators.iter().flatten().copied().collect();
+        let rent_paying_accounts = rent_paying_accounts(&genesis_config, &exempt);
+        if !rent_paying_accounts.is_empty() {
+            eprintln!("Error: --enforce-rent-exemption: accounts below the rent exempt minimum:");
+            for (pubkey, lamports, minimum_balance) in rent_paying_accounts {
+                eprintln!("  {pubkey}: {lamports} lamports, the minimum is {minimum_balance}");
+            }
+            process::exit(1);
+        }
+    }
+    print_account_data_summary(&genesis_config);
+    for warning in check_economics(&genesis_config) {
+        eprintln!("Warning [{}]: {}", warning.code, warning.message);
+    }
+
+    // Checking the archive size here, before the ledger is written, beats the tar error
+    // create_new_ledger would give.
+    if let Err(err) =
+        check_genesis_archive_unpacked_size(&genesis_config, max_genesis_archive_unpacked_size)
+    {
+        eprintln!("Error: {err}");
+        eprintln!("Largest accounts by data size:");
+        for (pubkey, account) in largest_accounts_by_data_size(&genesis_config, 10) {
+            eprintln!("  {pubkey}: {} bytes", account.data.len());
+        }
+        process::exit(1);
+    }
+
+    if matches.get_flag("show_timing") {
+        println!("Ticks per second: {}", ticks_per_second(&genesis_config));
+        println!("Slots per second: {}", slots_per_second(&genesis_config));
+        println!("Slots per year: {}", genesis_config.slots_per_year());
+        println!("Epochs per year: {}", epochs_per_year(&genesis_config));
+    }
+
+    if matches.get_flag("verbose") {
+        let mut labels = BTreeMap::new();
+        if let Some(faucet_pubkey) = faucet_pubkey {
+            labels.insert(faucet_pubkey, "faucet");
+        }
+        if reserve_lamports.is_some() {
+            labels.insert(RESERVE_PUBKEY, "reserve");
+        }
+        if matches.contains_id("spl_programs") {
+            for (program_id, _, label) in SPL_PROGRAMS {
+                labels.insert(program_id, label);
+            }
+        }
+        print_account_table(&genesis_config, &labels);
+    }
+
     // skip for development clusters
     // add_genesis_accounts(&mut genesis_config, issued_lamports - faucet_lamports);
 
@@ -584,97 +1210,379 @@ fn main() -> Result<(), Box<dyn std::error::Error>> {
     //         let address = parse_address(address, "address");
     //         let loader = parse_address(loader, "loader");
     //         let program_data_elf = parse_program_data(program);
-    //         let upgrade_authority_address = if upgrade_authority == "none" {
-    //             Pubkey::default()
-    //         } else {
-    //             upgrade_authority.parse::<Pubkey>().unwrap_or_else(|_| {
-    //                 read_keypair_file(upgrade_authority)
-    //                     .map(|keypair| keypair.pubkey())
-    //                     .unwrap_or_else(|err| {
-    //                         eprintln!(
-    //                             "Error: invalid upgrade_authority {upgrade_authority}: {err}"
-    //                         );
-    //                         process::exit(1);
-    //                     })
-    //             })
-    //         };
-    //
-    //         let (programdata_address, _) =
-    //             Pubkey::find_program_address(&[address.as_ref()], &loader);
-    //         let mut program_data = bincode::serialize(&UpgradeableLoaderState::ProgramData {
-    //             slot: 0,
-    //             upgrade_authority_address: Some(upgrade_authority_address),
-    //         })
-    //             .unwrap();
-    //         program_data.extend_from_slice(&program_data_elf);
-    //         genesis_config.add_account(
-    //             programdata_address,
-    //             AccountSharedData::from(Account {
-    //                 lamports: genesis_config.rent.minimum_balance(program_data.len()),
-    //                 data: program_data,
-    //                 owner: loader,
-    //                 executable: false,
-    //                 rent_epoch: 0,
-    //             }),
-    //         );
+    //         let upgrade_authority_address = parse_pubkey_or_none(upgrade_authority)
+    //             .unwrap_or_else(|err| {
+    //                 eprintln!("Error: invalid upgrade_authority {upgrade_authority}: {err}");
+    //                 process::exit(1);
+    //             });
     //
-    //         let program_data = bincode::serialize(&UpgradeableLoaderState::Program {
-    //             programdata_address,
-    //         })
-    //             .unwrap();
-    //         genesis_config.add_account(
+    //         add_upgradeable_program(
+    //             &mut genesis_config,
     //             address,
-    //             AccountSharedData::from(Account {
-    //                 lamports: genesis_config.rent.minimum_balance(program_data.len()),
-    //                 data: program_data,
-    //                 owner: loader,
-    //                 executable: true,
-    //                 rent_epoch: 0,
-    //             }),
+    //             &loader,
+    //             &program_data_elf,
+    //             upgrade_authority_address,
     //         );
     //     }
     // }
 
-    solana_logger::setup();
-    // This function creates the new ledger, which implicitly calculates the "Genesis hash" and "Shred version".
-    create_new_ledger(
-        &ledger_path,
-        &genesis_config,
-        max_genesis_archive_unpacked_size,
-        LedgerColumnOptions::default(),
+    if let Some(path) = matches.try_get_one::<String>("canonical_dump")? {
+        fs::write(path, canonical_dump(&genesis_config))?;
+    }
+    if let Some(path) = matches.try_get_one::<String>("json_output")? {
+        let genesis_hash = genesis_config.hash();
+        let json = serde_json::json!({
+            "genesis_hash": genesis_hash.to_string(),
+            "shred_version": compute_shred_version(&genesis_hash, None),
+            "config": serde_json::to_value(GenesisConfigJson::from(&genesis_config))?,
+        });
+        fs::write(path, serde_json::to_string_pretty(&json)?)?;
+    }
+
+    // --ledger is required unless --dry-run or --skip-ledger-write is set, so a missing path
+    // means one of those.
+    let Some(ledger_path) = ledger_path.filter(|_| !dry_run) else {
+        println!("{genesis_config}");
+        if matches.get_flag("skip_ledger_write") {
+            let genesis_hash = genesis_config.hash();
+            println!("Genesis hash: {genesis_hash}");
+            println!(
+                "Shred version: {}",
+                compute_shred_version(&genesis_hash, None)
+            );
+            check_expected_genesis_hash(&matches, &genesis_hash)?;
+        }
+        return Ok(());
+    };
+
+    let column_options = LedgerColumnOptions {
+        compression_type: match matches
+            .try_get_one::<String>("rocksdb_compression")?
+            .unwrap()
+            .as_str()
+        {
+            "none" => BlockstoreCompressionType::None,
+            "snappy" => BlockstoreCompressionType::Snappy,
+            "lz4" => BlockstoreCompressionType::Lz4,
+            "zlib" => BlockstoreCompressionType::Zlib,
+            _ => unreachable!(),
+        },
+        ..LedgerColumnOptions::default()
+    };
+
+    solana_logger::setup();
+    // This function creates the new ledger, which implicitly calculates the "Genesis hash" and "Shred version".
+    create_new_ledger(
+        &ledger_path,
+        &genesis_config,
+        max_genesis_archive_unpacked_size,
+        column_options,
     )?;
 
     // This line prints the final genesis configuration, which includes all the mentioned output values.
     // "Slots per year" and "Capitalization" are calculated within the Display implementation for GenesisConfig.
     println!("{genesis_config}");
+    // Explicit, stable lines for scripts that need the cluster identity. The hash is read back
+    // from the ledger so that it is the one validators will actually load.
+    let genesis_hash = read_genesis_hash(&ledger_path)?;
+    println!("Genesis hash: {genesis_hash}");
+    println!(
+        "Shred version: {}",
+        compute_shred_version(&genesis_hash, None)
+    );
+    check_expected_genesis_hash(&matches, &genesis_hash)
+}
+
+/// Exits with an error if `--expected-genesis-hash` was given and does not match `genesis_hash`.
+fn check_expected_genesis_hash(
+    matches: &ArgMatches,
+    genesis_hash: &Hash,
+) -> Result<(), Box<dyn std::error::Error>> {
+    if let Some(expected_genesis_hash) = matches.try_get_one::<Hash>("expected_genesis_hash")?
+        && expected_genesis_hash != genesis_hash
+    {
+        eprintln!(
+            "Error: genesis hash mismatch, expected {expected_genesis_hash} but the genesis \
+             config has {genesis_hash}"
+        );
+        process::exit(1);
+    }
     Ok(())
 }
 
+fn print_defaults(matches: &ArgMatches) -> Result<(), Box<dyn std::error::Error>> {
+    let cluster_type = *matches.get_one::<ClusterType>("cluster_type").unwrap();
+    let cluster = format!("--cluster-type {}", cluster_type_name(cluster_type));
+    let poh_config = PohConfig::default();
+    let hashes_per_tick = default_hashes_per_tick(cluster_type, poh_config.target_tick_duration);
+    let slots_per_epoch = default_slots_per_epoch(cluster_type);
+    let warmup_epochs = default_warmup_epochs(cluster_type);
+    let default_fee_rate_governor = FeeRateGovernor::default();
+    // Built the same way as when the genesis config is created, minus the accounts that come
+    // from arguments.
+    let mut fee_rate_governor = FeeRateGovernor::new(
+        default_fee_rate_governor.target_lamports_per_signature,
+        default_fee_rate_governor.target_signatures_per_slot,
+    );
+    fee_rate_governor.burn_percent = default_fee_rate_governor.burn_percent;
+    let mut genesis_config = GenesisConfig {
+        ticks_per_slot: clock::DEFAULT_TICKS_PER_SLOT,
+        poh_config: PohConfig {
+            hashes_per_tick: Some(hashes_per_tick),
+            ..poh_config
+        },
+        fee_rate_governor,
+        rent: Rent::default(),
+        epoch_schedule: EpochSchedule::custom(slots_per_epoch, slots_per_epoch, warmup_epochs),
+        cluster_type,
+        ..GenesisConfig::default()
+    };
+    let activate_all_features = default_activate_all_features(cluster_type);
+    if activate_all_features {
+        genesis_utils::activate_all_features(&mut genesis_config);
+    }
+    println!("{genesis_config}");
+
+    println!("Where the defaults come from:");
+    let hashes_per_tick_source = if cluster_type == ClusterType::Development {
+        format!("{cluster}, measured on this computer")
+    } else {
+        cluster.clone()
+    };
+    let features_source = if activate_all_features {
+        format!("feature accounts, {cluster} activates every feature")
+    } else {
+        format!("{cluster} activates no features")
+    };
+    let sources = [
+        ("Cluster type", "--cluster-type"),
+        ("Hashes per tick", &hashes_per_tick_source),
+        ("Slots per epoch", &cluster),
+        ("Warmup epochs", &cluster),
+        ("Capitalization", &features_source),
+        ("Ticks per slot", "clock::DEFAULT_TICKS_PER_SLOT"),
+        ("Target tick duration", "PohConfig::default()"),
+        ("Inflation", "Inflation::default()"),
+        ("Rent", "Rent::default()"),
+        ("FeeRateGovernor", "FeeRateGovernor::default()"),
+        (
+            "Creation time",
+            "GenesisConfig::default(), the current time",
+        ),
+    ];
+    for (name, source) in sources {
+        println!("  {name}: {source}");
+    }
+    Ok(())
+}
+
+fn export_genesis(matches: &ArgMatches) -> Result<(), Box<dyn std::error::Error>> {
+    let ledger_path = matches.get_one::<String>("ledger_path").unwrap();
+    let genesis_config = GenesisConfig::load(Path::new(ledger_path))?;
+    let genesis_config = GenesisConfigJson::from(&genesis_config);
+    let output = match matches.get_one::<String>("output_format").unwrap().as_str() {
+        "toml" => toml::to_string_pretty(&genesis_config)?,
+        _ => serde_json::to_string_pretty(&genesis_config)?,
+    };
+    match matches.get_one::<String>("output") {
+        Some(path) => fs::write(path, output)?,
+        None => println!("{output}"),
+    }
+    Ok(())
+}
+
+fn import_genesis(matches: &ArgMatches) -> Result<(), Box<dyn std::error::Error>> {
+    let input = matches.get_one::<String>("input").unwrap();
+    let ledger_path = matches.get_one::<String>("ledger_path").unwrap();
+    let contents = fs::read_to_string(input)?;
+    let json: GenesisConfigJson = if Path::new(input)
+        .extension()
+        .is_some_and(|ext| ext == "toml")
+    {
+        toml::from_str(&contents).map_err(|err| format!("{input}: {err}"))?
+    } else {
+        serde_json::from_str(&contents).map_err(|err| format!("{input}: {err}"))?
+    };
+    let genesis_config = GenesisConfig::try_from(json).map_err(|err| format!("{input}: {err}"))?;
+
+    solana_logger::setup();
+    create_new_ledger(
+        Path::new(ledger_path),
+        &genesis_config,
+        MAX_GENESIS_ARCHIVE_UNPACKED_SIZE,
+        LedgerColumnOptions::default(),
+    )?;
+    println!("{genesis_config}");
+    let genesis_hash = read_genesis_hash(Path::new(ledger_path))?;
+    println!("Genesis hash: {genesis_hash}");
+    println!(
+        "Shred version: {}",
+        compute_shred_version(&genesis_hash, None)
+    );
+    Ok(())
+}
+
+fn verify_genesis(matches: &ArgMatches) -> Result<(), Box<dyn std::error::Error>> {
+    let ledger_path = Path::new(matches.get_one::<String>("ledger_path").unwrap());
+    let genesis_file = ledger_path.join(DEFAULT_GENESIS_FILE);
+    if !genesis_file.is_file() {
+        eprintln!(
+            "Error: {} has no genesis config, {} does not exist",
+            ledger_path.display(),
+            genesis_file.display()
+        );
+        process::exit(1);
+    }
+    let genesis_config = GenesisConfig::load(ledger_path).unwrap_or_else(|err| {
+        eprintln!("Error: failed to load {}: {err}", genesis_file.display());
+        process::exit(1);
+    });
+    println!("{genesis_config}");
+    let issued_lamports = issued_lamports(&genesis_config).unwrap_or_else(|err| {
+        eprintln!("Error: {err}");
+        process::exit(1);
+    });
+    println!("Issued lamports: {issued_lamports}");
+    let genesis_hash = genesis_config.hash();
+    println!("Genesis hash: {genesis_hash}");
+    println!(
+        "Shred version: {}",
+        compute_shred_version(&genesis_hash, None)
+    );
+    Ok(())
+}
+
+/// Adds the SPL programs of [`SPL_PROGRAMS`] from the ELF files in `dir`, reporting every
+/// missing file at once.
+fn add_spl_programs(genesis_config: &mut GenesisConfig, dir: &Path) -> Result<(), String> {
+    if !dir.is_dir() {
+        return Err("not a directory".to_string());
+    }
+    let missing = SPL_PROGRAMS
+        .iter()
+        .map(|(_, file_name, _)| *file_name)
+        .filter(|file_name| !dir.join(file_name).is_file())
+        .collect::<Vec<_>>();
+    if !missing.is_empty() {
+        return Err(format!("missing {}", missing.join(", ")));
+    }
+    for (program_id, file_name, _) in SPL_PROGRAMS {
+        let path = dir.join(file_name);
+        let elf = fs::read(&path).map_err(|err| format!("{}: {err}", path.display()))?;
+        add_upgradeable_program(
+            genesis_config,
+            program_id,
+            &bpf_loader_upgradeable::id(),
+            &elf,
+            None,
+        );
+    }
+    Ok(())
+}
+
+/// Adds the program and program data accounts of an upgradeable BPF loader program holding
+/// `elf`, both rent exempt.
+fn add_upgradeable_program(
+    genesis_config: &mut GenesisConfig,
+    program_id: Pubkey,
+    loader: &Pubkey,
+    elf: &[u8],
+    upgrade_authority_address: Option<Pubkey>,
+) {
+    let (programdata_address, _) = Pubkey::find_program_address(&[program_id.as_ref()], loader);
+    let mut program_data = bincode::serialize(&UpgradeableLoaderState::ProgramData {
+        slot: 0,
+        upgrade_authority_address,
+    })
+    .unwrap();
+    // Without an authority the metadata serializes shorter, but the loader always expects the
+    // ELF at the same offset.
+    program_data.resize(UpgradeableLoaderState::size_of_programdata_metadata(), 0);
+    program_data.extend_from_slice(elf);
+    genesis_config.add_account(
+        programdata_address,
+        AccountSharedData::from(Account {
+            lamports: genesis_config.rent.minimum_balance(program_data.len()),
+            data: program_data,
+            owner: *loader,
+            executable: false,
+            rent_epoch: 0,
+        }),
+    );
+
+    let program = bincode::serialize(&UpgradeableLoaderState::Program {
+        programdata_address,
+    })
+    .unwrap();
+    genesis_config.add_account(
+        program_id,
+        AccountSharedData::from(Account {
+            lamports: genesis_config.rent.minimum_balance(program.len()),
+            data: program,
+            owner: *loader,
+            executable: true,
+            rent_epoch: 0,
+        }),
+    );
+}
+
+/// Returns the hash of the genesis config stored in the ledger at `ledger_path`.
+fn read_genesis_hash(ledger_path: &Path) -> Result<Hash, String> {
+    GenesisConfig::load(ledger_path)
+        .map(|genesis_config| genesis_config.hash())
+        .map_err(|err| {
+            format!(
+                "failed to read the genesis config back from {}: {err}",
+                ledger_path.display()
+            )
+        })
+}
+
 fn add_validator_accounts(
     genesis_config: &mut GenesisConfig,
-    pubkeys_iter: &mut Iter<Pubkey>,
+    validators: &[[Pubkey; 3]],
     lamports: u64,
     stake_lamports: u64,
     commission: u8,
     rent: &Rent,
     authorized_pubkey: Option<&Pubkey>,
 ) -> io::Result<()> {
+    for (pubkey, account) in validator_accounts(
+        validators,
+        lamports,
+        stake_lamports,
+        commission,
+        rent,
+        authorized_pubkey,
+    )? {
+        genesis_config.add_account(pubkey, account);
+    }
+    Ok(())
+}
+
+/// Genesis accounts in the order they are to be added.
+type GenesisAccounts = Vec<(Pubkey, AccountSharedData)>;
+
+/// Builds the identity, stake and vote accounts of each validator, in that order.
+fn validator_accounts(
+    validators: &[[Pubkey; 3]],
+    lamports: u64,
+    stake_lamports: u64,
+    commission: u8,
+    rent: &Rent,
+    authorized_pubkey: Option<&Pubkey>,
+) -> io::Result<GenesisAccounts> {
     rent_exempt_check(
         stake_lamports,
         rent.minimum_balance(StakeStateV2::size_of()),
     )?;
 
-    loop {
-        let Some(identity_pubkey) = pubkeys_iter.next() else {
-            break;
-        };
-        let vote_pubkey = pubkeys_iter.next().unwrap();
-        let stake_pubkey = pubkeys_iter.next().unwrap();
-
-        genesis_config.add_account(
+    let mut accounts = vec![];
+    for [identity_pubkey, vote_pubkey, stake_pubkey] in validators {
+        accounts.push((
             *identity_pubkey,
             AccountSharedData::new(lamports, 0, &system_program::id()),
-        );
+        ));
 
         let vote_account = vote_state::create_account_with_authorized(
             identity_pubkey,
@@ -684,7 +1592,7 @@ fn add_validator_accounts(
             VoteStateV3::get_rent_exempt_reserve(rent).max(1),
         );
 
-        genesis_config.add_account(
+        accounts.push((
             *stake_pubkey,
             stake_state::create_account(
                 authorized_pubkey.unwrap_or(identity_pubkey),
@@ -693,12 +1601,886 @@ fn add_validator_accounts(
                 rent,
                 stake_lamports,
             ),
+        ));
+        accounts.push((*vote_pubkey, vote_account));
+    }
+    Ok(accounts)
+}
+
+/// An account of a YAML --primordial-accounts-file, keyed by its pubkey.
+#[derive(Debug, Deserialize)]
+struct PrimordialAccount {
+    balance: u64,
+    #[serde(default)]
+    owner: Option<String>,
+    /// Base64 encoded
+    #[serde(default)]
+    data: String,
+    #[serde(default)]
+    executable: bool,
+}
+
+/// Reads the accounts of a --primordial-accounts-file, as CSV when its extension is `.csv`
+/// and as YAML otherwise, along with the lamports they hold.
+fn read_genesis_accounts(file: &str) -> io::Result<(Vec<(Pubkey, AccountSharedData)>, u64)> {
+    let is_csv = Path::new(file)
+        .extension()
+        .is_some_and(|extension| extension.eq_ignore_ascii_case("csv"));
+    let accounts = if is_csv {
+        read_primordial_accounts_csv(file)
+    } else {
+        serde_yaml::from_reader::<_, BTreeMap<String, PrimordialAccount>>(fs::File::open(file)?)
+            .map_err(|err| err.to_string())
+            .and_then(|accounts| {
+                accounts
+                    .into_iter()
+                    .map(|(pubkey, account)| primordial_account(&pubkey, account))
+                    .collect()
+            })
+    }
+    .map_err(|err| io::Error::other(format!("{file}: {err}")))?;
+
+    let lamports = accounts
+        .iter()
+        .try_fold(0u64, |total, (_, account)| {
+            total.checked_add(account.lamports())
+        })
+        .ok_or_else(|| {
+            io::Error::other(format!("{file}: the balances add up to more than u64::MAX"))
+        })?;
+    Ok((accounts, lamports))
+}
+
+/// Sorts genesis accounts by pubkey for --reproducible, rejecting a pubkey listed more than
+/// once, whose surviving entry would otherwise depend on the order of the inputs.
+fn sort_genesis_accounts(accounts: &mut [(Pubkey, AccountSharedData)]) -> Result<(), String> {
+    accounts.sort_by_key(|(pubkey, _)| *pubkey);
+    match accounts.windows(2).find(|pair| pair[0].0 == pair[1].0) {
+        Some(pair) => Err(format!(
+            "{} is listed more than once in the --primordial-accounts-file inputs",
+            pair[0].0
+        )),
+        None => Ok(()),
+    }
+}
+
+/// Reads the `pubkey,balance[,owner]` rows of a CSV --primordial-accounts-file. A
+/// `pubkey,balance,...` header row, blank lines and `#` comments are skipped.
+fn read_primordial_accounts_csv(file: &str) -> Result<Vec<(Pubkey, AccountSharedData)>, String> {
+    let contents = fs::read_to_string(file).map_err(|err| format!("unable to read: {err}"))?;
+    let mut accounts = vec![];
+    let mut listed = HashSet::new();
+    for (index, line) in contents.lines().enumerate() {
+        let line = line.trim();
+        if line.is_empty()
+            || line.starts_with('#')
+            || (index == 0 && line.to_ascii_lowercase().starts_with("pubkey,"))
+        {
+            continue;
+        }
+        let values = line.split(',').map(str::trim).collect::<Vec<_>>();
+        let (pubkey, balance, owner) = match values.as_slice() {
+            [pubkey, balance] => (pubkey, balance, None),
+            [pubkey, balance, owner] => (pubkey, balance, Some(owner.to_string())),
+            _ => {
+                return Err(format!(
+                    "line {}: expected pubkey,balance[,owner], but {} values were given",
+                    index + 1,
+                    values.len()
+                ));
+            }
+        };
+        let balance = balance
+            .parse::<u64>()
+            .map_err(|err| format!("line {}: invalid balance {balance}: {err}", index + 1))?;
+        let account = PrimordialAccount {
+            balance,
+            owner: owner.filter(|owner| !owner.is_empty()),
+            data: String::new(),
+            executable: false,
+        };
+        let (pubkey, account) = primordial_account(pubkey, account)
+            .map_err(|err| format!("line {}: {err}", index + 1))?;
+        if !listed.insert(pubkey) {
+            return Err(format!(
+                "line {}: {pubkey} is listed more than once",
+                index + 1
+            ));
+        }
+        accounts.push((pubkey, account));
+    }
+    Ok(accounts)
+}
+
+/// Builds the genesis account of a --primordial-accounts-file entry, owned by the system
+/// program unless it names another owner.
+fn primordial_account(
+    pubkey: &str,
+    account: PrimordialAccount,
+) -> Result<(Pubkey, AccountSharedData), String> {
+    let pubkey = parse_pubkey(pubkey)?;
+    let owner = match &account.owner {
+        Some(owner) => parse_pubkey(owner).map_err(|err| format!("{pubkey}: owner: {err}"))?,
+        None => system_program::id(),
+    };
+    let data = BASE64_STANDARD
+        .decode(&account.data)
+        .map_err(|err| format!("{pubkey}: invalid base64 data: {err}"))?;
+    Ok((
+        pubkey,
+        AccountSharedData::from(Account {
+            lamports: account.balance,
+            data,
+            owner,
+            executable: account.executable,
+            rent_epoch: 0,
+        }),
+    ))
+}
+
+/// A validator entry of a --validator-accounts-file.
+#[derive(Debug, Deserialize)]
+struct ValidatorAccountInfo {
+    identity_account: String,
+    vote_account: String,
+    stake_account: String,
+    balance_lamports: u64,
+    stake_lamports: u64,
+    #[serde(default, deserialize_with = "deserialize_commission")]
+    commission: Option<u8>,
+}
+
+#[derive(Debug, Deserialize)]
+struct ValidatorAccountsFile {
+    validator_accounts: Vec<ValidatorAccountInfo>,
+}
+
+/// Accepts a commission given either as a number or as a string such as "5%", with the same
+/// 0 to 100 range as --vote-commission-percentage.
+fn deserialize_commission<'de, D>(deserializer: D) -> Result<Option<u8>, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    #[derive(Deserialize)]
+    #[serde(untagged)]
+    enum Commission {
+        Number(u64),
+        Text(String),
+    }
+
+    let commission = match Commission::deserialize(deserializer)? {
+        Commission::Number(number) => number.to_string(),
+        Commission::Text(text) => text,
+    };
+    parse_percentage(&commission)
+        .map(Some)
+        .map_err(serde::de::Error::custom)
+}
+
+/// Constraints of an --accounts-schema file on the loaded genesis accounts and on the entries
+/// of --validator-accounts-file.
+#[derive(Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct AccountsSchema {
+    #[serde(default)]
+    required_fields: Vec<String>,
+    #[serde(default)]
+    allowed_owners: Option<Vec<String>>,
+    #[serde(default)]
+    max_data_len: Option<usize>,
+}
+
+fn load_accounts_schema(file: &str) -> io::Result<AccountsSchema> {
+    let schema: AccountsSchema = serde_json::from_reader(fs::File::open(file)?)
+        .map_err(|err| io::Error::other(format!("{file}: {err}")))?;
+    for owner in schema.allowed_owners.iter().flatten() {
+        parse_pubkey(owner).map_err(|err| io::Error::other(format!("{file}: {err}")))?;
+    }
+    Ok(schema)
+}
+
+/// Returns how `account`, loaded from `location`, breaks the owner and data constraints of
+/// `schema`.
+fn account_schema_violations(
+    schema: &AccountsSchema,
+    location: &str,
+    pubkey: &Pubkey,
+    account: &AccountSharedData,
+) -> Vec<String> {
+    let mut violations = vec![];
+    if let Some(allowed_owners) = &schema.allowed_owners
+        && !allowed_owners.contains(&account.owner().to_string())
+    {
+        violations.push(format!(
+            "{location}: account {pubkey} is owned by {}, which is not an allowed owner",
+            account.owner()
+        ));
+    }
+    if let Some(max_data_len) = schema.max_data_len
+        && account.data().len() > max_data_len
+    {
+        violations.push(format!(
+            "{location}: account {pubkey} has {} bytes of data, more than the max_data_len \
+             of {max_data_len}",
+            account.data().len()
+        ));
+    }
+    violations
+}
+
+/// Builds the accounts of the validators listed in a --validator-accounts-file, each with the
+/// vote commission of its entry or `commission` when the entry has none, and returns them
+/// along with how the entries and accounts break `schema`.
+fn read_validator_accounts(
+    file: &str,
+    commission: u8,
+    rent: &Rent,
+    schema: Option<&AccountsSchema>,
+) -> io::Result<(GenesisAccounts, Vec<String>)> {
+    let raw_file: serde_yaml::Value = serde_yaml::from_reader(fs::File::open(file)?)
+        .map_err(|err| io::Error::other(format!("{file}: {err}")))?;
+    let accounts_file: ValidatorAccountsFile = serde_yaml::from_value(raw_file.clone())
+        .map_err(|err| io::Error::other(format!("{file}: {err}")))?;
+    let validators = accounts_file
+        .validator_accounts
+        .iter()
+        .enumerate()
+        .map(|(index, info)| {
+            let resolve = |pubkey: &str| {
+                parse_pubkey(pubkey).map_err(|err| format!("validator #{}: {err}", index + 1))
+            };
+            Ok([
+                resolve(&info.identity_account)?,
+                resolve(&info.vote_account)?,
+                resolve(&info.stake_account)?,
+            ])
+        })
+        .collect::<Result<Vec<_>, String>>()
+        .and_then(|validators| {
+            validate_bootstrap_validators(&validators)?;
+            Ok(validators)
+        })
+        .map_err(|err| io::Error::other(format!("{file}: {err}")))?;
+    let mut accounts = vec![];
+    let mut violations = vec![];
+    for (index, (info, validator)) in accounts_file
+        .validator_accounts
+        .iter()
+        .zip(&validators)
+        .enumerate()
+    {
+        let validator_accounts = validator_accounts(
+            std::slice::from_ref(validator),
+            info.balance_lamports,
+            info.stake_lamports,
+            info.commission.unwrap_or(commission),
+            rent,
+            None,
+        )?;
+        if let Some(schema) = schema {
+            let location = format!("{file}: validator #{}", index + 1);
+            let entry = &raw_file["validator_accounts"][index];
+            violations.extend(
+                schema
+                    .required_fields
+                    .iter()
+                    .filter(|field| entry.get(field.as_str()).is_none())
+                    .map(|field| format!("{location}: required field '{field}' is missing")),
+            );
+            for (pubkey, account) in &validator_accounts {
+                violations.extend(account_schema_violations(
+                    schema, &location, pubkey, account,
+                ));
+            }
+        }
+        accounts.extend(validator_accounts);
+    }
+    Ok((accounts, violations))
+}
+
+/// Marks a delegated stake account as deactivating from epoch 0.
+fn deactivate_stake_at_genesis(stake_account: &mut Account) -> io::Result<()> {
+    if stake_account.owner != stake::id() {
+        return Err(io::Error::other(format!(
+            "not a stake account, it is owned by {}",
+            stake_account.owner
+        )));
+    }
+    let mut stake_state = bincode::deserialize::<StakeStateV2>(&stake_account.data)
+        .map_err(|err| io::Error::other(format!("invalid stake account: {err}")))?;
+    let StakeStateV2::Stake(_, stake, _) = &mut stake_state else {
+        return Err(io::Error::other("stake account is not delegated"));
+    };
+    stake.delegation.deactivation_epoch = 0;
+    bincode::serialize_into(&mut stake_account.data[..], &stake_state)
+        .map_err(|err| io::Error::other(format!("failed to write stake account: {err}")))
+}
+
+/// Renders the genesis parameters as `key = value` lines sorted by key.
+fn canonical_dump(genesis_config: &GenesisConfig) -> String {
+    canonical_entries(genesis_config)
+        .iter()
+        .map(|(key, value)| format!("{key} = {value}\n"))
+        .collect()
+}
+
+/// Flattens the genesis parameters into dotted keys. Durations are in microseconds and
+/// account data is reduced to its length and SHA-256 hash.
+fn canonical_entries(genesis_config: &GenesisConfig) -> BTreeMap<String, String> {
+    let GenesisConfig {
+        creation_time,
+        accounts,
+        native_instruction_processors,
+        rewards_pools,
+        ticks_per_slot,
+        poh_config,
+        fee_rate_governor,
+        rent,
+        inflation,
+        epoch_schedule,
+        cluster_type,
+        ..
+    } = genesis_config;
+    let mut entries = BTreeMap::<String, String>::new();
+    let mut add = |key: &str, value: &dyn Display| {
+        entries.insert(key.to_string(), value.to_string());
+    };
+    let optional = |value: Option<u64>| value.map_or("none".to_string(), |v| v.to_string());
+
+    add("cluster_type", &format!("{cluster_type:?}"));
+    add("creation_time", creation_time);
+    add("ticks_per_slot", ticks_per_slot);
+    add(
+        "poh.target_tick_duration_us",
+        &poh_config.target_tick_duration.as_micros(),
+    );
+    add(
+        "poh.target_tick_count",
+        &optional(poh_config.target_tick_count),
+    );
+    add("poh.hashes_per_tick", &optional(poh_config.hashes_per_tick));
+    add(
+        "fee_rate_governor.target_lamports_per_signature",
+        &fee_rate_governor.target_lamports_per_signature,
+    );
+    add(
+        "fee_rate_governor.target_signatures_per_slot",
+        &fee_rate_governor.target_signatures_per_slot,
+    );
+    add(
+        "fee_rate_governor.min_lamports_per_signature",
+        &fee_rate_governor.min_lamports_per_signature,
+    );
+    add(
+        "fee_rate_governor.max_lamports_per_signature",
+        &fee_rate_governor.max_lamports_per_signature,
+    );
+    add(
+        "fee_rate_governor.burn_percent",
+        &fee_rate_governor.burn_percent,
+    );
+    add("rent.lamports_per_byte_year", &rent.lamports_per_byte_year);
+    add("rent.exemption_threshold", &rent.exemption_threshold);
+    add("rent.burn_percent", &rent.burn_percent);
+    add("inflation.initial", &inflation.initial);
+    add("inflation.terminal", &inflation.terminal);
+    add("inflation.taper", &inflation.taper);
+    add("inflation.foundation", &inflation.foundation);
+    add("inflation.foundation_term", &inflation.foundation_term);
+    add(
+        "epoch_schedule.slots_per_epoch",
+        &epoch_schedule.slots_per_epoch,
+    );
+    add(
+        "epoch_schedule.leader_schedule_slot_offset",
+        &epoch_schedule.leader_schedule_slot_offset,
+    );
+    add("epoch_schedule.warmup", &epoch_schedule.warmup);
+    add(
+        "epoch_schedule.first_normal_epoch",
+        &epoch_schedule.first_normal_epoch,
+    );
+    add(
+        "epoch_schedule.first_normal_slot",
+        &epoch_schedule.first_normal_slot,
+    );
+    for (name, program_id) in native_instruction_processors {
+        add(&format!("native_instruction_processors.{name}"), program_id);
+    }
+    for (prefix, accounts) in [("accounts", accounts), ("rewards_pools", rewards_pools)] {
+        for (pubkey, account) in accounts {
+            let key = format!("{prefix}.{pubkey}");
+            add(&format!("{key}.lamports"), &account.lamports);
+            add(&format!("{key}.owner"), &account.owner);
+            add(&format!("{key}.executable"), &account.executable);
+            add(&format!("{key}.data_len"), &account.data.len());
+            add(&format!("{key}.data_hash"), &hash(&account.data));
+        }
+    }
+
+    entries
+}
+
+/// Rejects accounts holding more data than the runtime allows a single account to have.
+fn validate_account_data_sizes(genesis_config: &GenesisConfig) -> Result<(), String> {
+    for (pubkey, account) in &genesis_config.accounts {
+        if account.data.len() as u64 > MAX_PERMITTED_DATA_LENGTH {
+            return Err(format!(
+                "account {pubkey} has {} bytes of data, the maximum is {MAX_PERMITTED_DATA_LENGTH}",
+                account.data.len()
+            ));
+        }
+    }
+    Ok(())
+}
+
+/// Lists the genesis accounts not in `exempt` that hold less than the rent exempt minimum for
+/// their data length, with their balance and that minimum.
+fn rent_paying_accounts(
+    genesis_config: &GenesisConfig,
+    exempt: &HashSet<Pubkey>,
+) -> Vec<(Pubkey, u64, u64)> {
+    genesis_config
+        .accounts
+        .iter()
+        .filter(|(pubkey, _)| !exempt.contains(pubkey))
+        .filter_map(|(pubkey, account)| {
+            let minimum_balance = genesis_config.rent.minimum_balance(account.data.len());
+            (account.lamports < minimum_balance).then_some((
+                *pubkey,
+                account.lamports,
+                minimum_balance,
+            ))
+        })
+        .collect()
+}
+
+/// Rejects a tick and slot combination whose epochs are too short to be usable.
+fn validate_ticks_per_epoch(
+    ticks_per_slot: u64,
+    slots_per_epoch: Slot,
+    min_ticks_per_epoch: u64,
+) -> Result<(), String> {
+    let ticks_per_epoch = ticks_per_slot.saturating_mul(slots_per_epoch);
+    if ticks_per_epoch < min_ticks_per_epoch {
+        return Err(format!(
+            "--ticks-per-slot {ticks_per_slot} and --slots-per-epoch {slots_per_epoch} give \
+             {ticks_per_epoch} ticks per epoch, the minimum is {min_ticks_per_epoch} \
+             (--min-ticks-per-epoch)"
+        ));
+    }
+    if slots_per_epoch < MINIMUM_SLOTS_PER_EPOCH {
+        return Err(format!(
+            "--slots-per-epoch {slots_per_epoch} is below the minimum of {MINIMUM_SLOTS_PER_EPOCH}"
+        ));
+    }
+    Ok(())
+}
+
+/// Checks that --max-genesis-archive-unpacked-size leaves room for the serialized genesis
+/// config and the ledger archived along with it.
+fn check_genesis_archive_unpacked_size(
+    genesis_config: &GenesisConfig,
+    max_genesis_archive_unpacked_size: u64,
+) -> Result<(), String> {
+    let genesis_size = bincode::serialized_size(genesis_config).map_err(|err| err.to_string())?;
+    let min_size = genesis_size.saturating_add(GENESIS_ARCHIVE_LEDGER_MARGIN);
+    if max_genesis_archive_unpacked_size < min_size {
+        return Err(format!(
+            "--max-genesis-archive-unpacked-size {max_genesis_archive_unpacked_size} is too \
+             small, the minimum is {min_size}: {genesis_size} bytes of serialized genesis \
+             config and {GENESIS_ARCHIVE_LEDGER_MARGIN} for the ledger archived with it"
+        ));
+    }
+    Ok(())
+}
+
+fn largest_accounts_by_data_size(
+    genesis_config: &GenesisConfig,
+    count: usize,
+) -> Vec<(&Pubkey, &Account)> {
+    let mut accounts = genesis_config.accounts.iter().collect::<Vec<_>>();
+    accounts.sort_by_key(|(_, account)| Reverse(account.data.len()));
+    accounts.truncate(count);
+    accounts
+}
+
+fn print_account_data_summary(genesis_config: &GenesisConfig) {
+    let total_data_bytes = genesis_config
+        .accounts
+        .values()
+        .map(|account| account.data.len())
+        .sum::<usize>();
+    print!(
+        "Accounts: {}, total data bytes: {total_data_bytes}",
+        genesis_config.accounts.len()
+    );
+    match largest_accounts_by_data_size(genesis_config, 1).first() {
+        Some((pubkey, account)) => {
+            println!(", largest account: {pubkey} ({} bytes)", account.data.len())
+        }
+        None => println!(),
+    }
+}
+
+/// A combination of genesis settings that is valid but most likely not intended.
+struct ConfigWarning {
+    /// Stable identifier of the check, for tooling that consumes the warnings.
+    code: &'static str,
+    message: String,
+}
+
+/// Cross-checks the inflation and rent settings against the accounts baked into genesis.
+fn check_economics(genesis_config: &GenesisConfig) -> Vec<ConfigWarning> {
+    let mut warnings = vec![];
+    if genesis_config.inflation == Inflation::new_disabled() {
+        let stake_accounts = genesis_config
+            .accounts
+            .values()
+            .filter(|account| account.owner == stake::id())
+            .count();
+        let rewards_pools = genesis_config.rewards_pools.len();
+        if stake_accounts > 0 || rewards_pools > 0 {
+            warnings.push(ConfigWarning {
+                code: "inflation-disabled-with-rewards",
+                message: format!(
+                    "inflation is disabled, but {stake_accounts} stake accounts and \
+                     {rewards_pools} rewards pools are baked in and will never earn rewards"
+                ),
+            });
+        }
+    }
+    if genesis_config.rent.lamports_per_byte_year == 0 {
+        let default_rent = Rent::default();
+        let rent_paying_accounts = genesis_config
+            .accounts
+            .values()
+            .filter(|account| account.lamports < default_rent.minimum_balance(account.data.len()))
+            .count();
+        if rent_paying_accounts > 0 {
+            warnings.push(ConfigWarning {
+                code: "rent-disabled-with-rent-paying-accounts",
+                message: format!(
+                    "rent is disabled, but {rent_paying_accounts} accounts are below the default \
+                     rent exempt minimum and would pay rent on a cluster that collects it"
+                ),
+            });
+        }
+    }
+    warnings
+}
+
+/// Rejects a faucet balance below the rent exempt minimum of its own account, which would
+/// fail the first airdrop, and warns about one too small to keep a public cluster going.
+fn check_faucet_lamports(
+    faucet_lamports: u64,
+    rent: &Rent,
+    cluster_type: ClusterType,
+) -> Result<Option<ConfigWarning>, String> {
+    let rent_exempt_minimum = rent.minimum_balance(0);
+    if faucet_lamports < rent_exempt_minimum {
+        return Err(format!(
+            "--faucet-lamports {faucet_lamports} is below {rent_exempt_minimum}, the rent \
+             exempt minimum of the faucet account"
+        ));
+    }
+    let recommended_lamports = 1_000 * LAMPORTS_PER_SOL;
+    if cluster_type != ClusterType::Development && faucet_lamports < recommended_lamports {
+        return Ok(Some(ConfigWarning {
+            code: "faucet-underfunded",
+            message: format!(
+                "--faucet-lamports {faucet_lamports} is less than {recommended_lamports} \
+                 (1000 SOL), airdrops on a {} cluster will drain it quickly",
+                cluster_type_name(cluster_type)
+            ),
+        }));
+    }
+    Ok(None)
+}
+
+/// Sums the lamports of every genesis account, failing rather than wrapping around when the
+/// total does not fit in a u64.
+fn issued_lamports(genesis_config: &GenesisConfig) -> Result<u64, String> {
+    genesis_config
+        .accounts
+        .iter()
+        .try_fold(0u64, |total, (pubkey, account)| {
+            total.checked_add(account.lamports).ok_or_else(|| {
+                format!(
+                    "the genesis accounts hold more than {} lamports in total, adding the \
+                     {} lamports of {pubkey} overflows",
+                    u64::MAX,
+                    account.lamports
+                )
+            })
+        })
+}
+
+/// Sums the faucet lamports and the identity and stake lamports of `validator_count`
+/// bootstrap validators, failing on overflow and when the total exceeds `max_lamports`.
+fn check_faucet_and_validator_lamports(
+    faucet_lamports: u64,
+    validator_count: usize,
+    validator_lamports: u64,
+    validator_stake_lamports: u64,
+    max_lamports: Option<u64>,
+) -> Result<(), String> {
+    let total = validator_lamports
+        .checked_add(validator_stake_lamports)
+        .and_then(|lamports| lamports.checked_mul(validator_count as u64))
+        .and_then(|lamports| lamports.checked_add(faucet_lamports))
+        .ok_or_else(|| {
+            format!(
+                "--faucet-lamports {faucet_lamports} and {validator_count} bootstrap validators \
+                 with {validator_lamports} lamports and {validator_stake_lamports} stake \
+                 lamports each hold more than {} lamports in total",
+                u64::MAX
+            )
+        })?;
+    if let Some(max_lamports) = max_lamports
+        && total > max_lamports
+    {
+        return Err(format!(
+            "the faucet and bootstrap validators hold {total} lamports, more than \
+             --max-faucet-and-validator-lamports {max_lamports}"
+        ));
+    }
+    Ok(())
+}
+
+/// Rejects sleeping instead of hashing outside of development clusters, where the PoH has to
+/// prove elapsed time, and warns when they hash less per tick than the default.
+fn check_hashes_per_tick(
+    hashes_per_tick: Option<u64>,
+    cluster_type: ClusterType,
+) -> Result<Option<ConfigWarning>, String> {
+    if cluster_type == ClusterType::Development {
+        return Ok(None);
+    }
+    let Some(hashes_per_tick) = hashes_per_tick else {
+        return Err(format!(
+            "--hashes-per-tick sleep is only for development clusters, a {} cluster has to \
+             hash",
+            cluster_type_name(cluster_type)
+        ));
+    };
+    if hashes_per_tick < clock::DEFAULT_HASHES_PER_TICK {
+        return Ok(Some(ConfigWarning {
+            code: "hashes-per-tick-low",
+            message: format!(
+                "--hashes-per-tick {hashes_per_tick} is below the {} default, ticks on a {} \
+                 cluster will prove little elapsed time",
+                clock::DEFAULT_HASHES_PER_TICK,
+                cluster_type_name(cluster_type)
+            ),
+        }));
+    }
+    Ok(None)
+}
+
+/// Prints one row per account, in pubkey order. Accounts below their rent exempt minimum
+/// are flagged with a warning sign.
+fn print_account_table(genesis_config: &GenesisConfig, labels: &BTreeMap<Pubkey, &str>) {
+    println!(
+        "  {:<44} {:>20} {:>20} {:<44} {:>10} {:>10} Label",
+        "Pubkey", "Lamports", "Rent exempt minimum", "Owner", "Data len", "Executable"
+    );
+    for (pubkey, account) in &genesis_config.accounts {
+        let minimum_balance = genesis_config.rent.minimum_balance(account.data.len());
+        let flag = if account.lamports < minimum_balance {
+            "⚠"
+        } else {
+            " "
+        };
+        println!(
+            "{flag} {:<44} {:>20} {minimum_balance:>20} {:<44} {:>10} {:>10} {}",
+            // Pubkey's Display ignores width, so pad its string form instead.
+            pubkey.to_string(),
+            account.lamports,
+            account.owner.to_string(),
+            account.data.len(),
+            account.executable,
+            labels.get(pubkey).unwrap_or(&"")
+        );
+    }
+}
+
+fn cluster_type_name(cluster_type: ClusterType) -> &'static str {
+    match cluster_type {
+        ClusterType::Development => "development",
+        ClusterType::Devnet => "devnet",
+        ClusterType::Testnet => "testnet",
+        ClusterType::MainnetBeta => "mainnet-beta",
+    }
+}
+
+/// Resolves a --hashes-per-tick value, `None` standing for sleep. `auto` is rejected when
+/// `reproducible` is set, as on development clusters it depends on this computer's hash rate.
+fn parse_hashes_per_tick(
+    value: &str,
+    cluster_type: ClusterType,
+    target_tick_duration: Duration,
+    reproducible: bool,
+) -> Result<Option<u64>, String> {
+    match value {
+        "auto" if reproducible => Err(
+            "--reproducible needs an explicit --hashes-per-tick, a number or sleep, since auto \
+             may measure the hash rate of this computer"
+                .to_string(),
+        ),
+        "auto" => Ok(Some(default_hashes_per_tick(
+            cluster_type,
+            target_tick_duration,
+        ))),
+        "sleep" => Ok(None),
+        value => value
+            .parse::<u64>()
+            .map(Some)
+            .map_err(|err| format!("invalid value for --hashes-per-tick: {value}: {err}")),
+    }
+}
+
+/// Hashes per tick for `--hashes-per-tick auto`. Development clusters use half of what this
+/// computer can hash within `target_tick_duration`.
+fn default_hashes_per_tick(cluster_type: ClusterType, target_tick_duration: Duration) -> u64 {
+    match cluster_type {
+        ClusterType::Development => {
+            compute_hashes_per_tick(target_tick_duration, 1_000_000) / 2 // use 50% of peak ability
+        }
+        ClusterType::Devnet | ClusterType::Testnet | ClusterType::MainnetBeta => {
+            clock::DEFAULT_HASHES_PER_TICK
+        }
+    }
+}
+
+fn default_slots_per_epoch(cluster_type: ClusterType) -> Slot {
+    match cluster_type {
+        ClusterType::Development => clock::DEFAULT_DEV_SLOTS_PER_EPOCH,
+        ClusterType::Devnet | ClusterType::Testnet | ClusterType::MainnetBeta => {
+            clock::DEFAULT_SLOTS_PER_EPOCH
+        }
+    }
+}
+
+fn default_warmup_epochs(cluster_type: ClusterType) -> bool {
+    cluster_type == ClusterType::Development
+}
+
+/// Development clusters start with every feature active, the others with none, leaving their
+/// activation to feature gate proposals.
+fn default_activate_all_features(cluster_type: ClusterType) -> bool {
+    cluster_type == ClusterType::Development
+}
+
+/// Parses a `PUBKEY:SLOT` value of --activate-feature.
+fn parse_feature_activation(value: &str) -> Result<(Pubkey, Slot), String> {
+    let (pubkey, slot) = value
+        .rsplit_once(':')
+        .ok_or_else(|| format!("expected PUBKEY:SLOT, got {value}"))?;
+    let pubkey =
+        parse_pubkey(pubkey).map_err(|err| format!("invalid feature pubkey {pubkey}: {err}"))?;
+    let slot = parse_slot(slot).map_err(|err| format!("invalid activation slot {slot}: {err}"))?;
+    Ok((pubkey, slot))
+}
+
+/// Adds a feature account for each of `activations`, activated at its slot. A feature may only
+/// be listed once, and one unknown to this build is added with a warning, since it may simply
+/// be newer.
+fn add_feature_accounts(
+    genesis_config: &mut GenesisConfig,
+    activations: &[(Pubkey, Slot)],
+) -> Result<(), String> {
+    let mut listed = HashSet::new();
+    for (feature_id, _) in activations {
+        if !listed.insert(feature_id) {
+            return Err(format!("feature {feature_id} is listed more than once"));
+        }
+        if !agave_feature_set::FEATURE_NAMES.contains_key(feature_id) {
+            eprintln!("Warning: {feature_id} is not a feature known to this build");
+        }
+    }
+    let lamports = genesis_config
+        .rent
+        .minimum_balance(Feature::size_of())
+        .max(1);
+    for &(feature_id, slot) in activations {
+        genesis_config.add_account(
+            feature_id,
+            feature::create_account(
+                &Feature {
+                    activated_at: Some(slot),
+                },
+                lamports,
+            ),
         );
-        genesis_config.add_account(*vote_pubkey, vote_account);
     }
     Ok(())
 }
 
+fn ticks_per_second(genesis_config: &GenesisConfig) -> f64 {
+    1.0 / genesis_config.poh_config.target_tick_duration.as_secs_f64()
+}
+
+fn slots_per_second(genesis_config: &GenesisConfig) -> f64 {
+    ticks_per_second(genesis_config) / genesis_config.ticks_per_slot as f64
+}
+
+fn epochs_per_year(genesis_config: &GenesisConfig) -> f64 {
+    genesis_config.slots_per_year() / genesis_config.epoch_schedule.slots_per_epoch as f64
+}
+
+/// Checks that no pubkey plays more than one role within a (identity, vote, stake) triple.
+fn validate_bootstrap_validators(validators: &[[Pubkey; 3]]) -> Result<(), String> {
+    for (index, [identity, vote, stake]) in validators.iter().enumerate() {
+        if identity == vote || identity == stake || vote == stake {
+            return Err(format!(
+                "validator #{} reuses a pubkey across roles: \
+                 identity {identity}, vote {vote}, stake {stake}",
+                index + 1
+            ));
+        }
+    }
+    Ok(())
+}
+
+/// Reads bootstrap validators from a file holding one `IDENTITY_PUBKEY VOTE_PUBKEY STAKE_PUBKEY`
+/// triple per line, separated either by commas as in a CSV file or by whitespace. Blank lines
+/// and lines starting with `#` are skipped.
+fn read_bootstrap_validators_file(path: &str) -> Result<Vec<[Pubkey; 3]>, String> {
+    let contents = fs::read_to_string(path).map_err(|err| format!("unable to read: {err}"))?;
+    let mut validators = vec![];
+    for (index, line) in contents.lines().enumerate() {
+        let line = line.trim();
+        if line.is_empty() || line.starts_with('#') {
+            continue;
+        }
+        // Rows are comma separated as in a CSV file, or whitespace separated.
+        let values = if line.contains(',') {
+            line.split(',')
+                .map(|value| value.trim().to_string())
+                .collect::<Vec<_>>()
+        } else {
+            line.split_whitespace()
+                .map(String::from)
+                .collect::<Vec<_>>()
+        };
+        if values.iter().any(String::is_empty) {
+            return Err(format!("line {}: empty value", index + 1));
+        }
+        if values.len() != 3 {
+            return Err(format!(
+                "line {}: expected IDENTITY_PUBKEY VOTE_PUBKEY STAKE_PUBKEY, but {} values were given",
+                index + 1,
+                values.len()
+            ));
+        }
+        let triple = parse_triples(&values, parse_pubkey)
+            .map_err(|err| format!("line {}: {err}", index + 1))?;
+        validators.extend(triple);
+    }
+    Ok(validators)
+}
+
 fn rent_exempt_check(stake_lamports: u64, exempt: u64) -> io::Result<()> {
     if stake_lamports < exempt {
         Err(io::Error::other(format!(
@@ -708,3 +2490,806 @@ fn rent_exempt_check(stake_lamports: u64, exempt: u64) -> io::Result<()> {
         Ok(())
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Writes `contents` to `name` in `dir` and returns its path.
+    fn write_file(dir: &tempfile::TempDir, name: &str, contents: &str) -> String {
+        let path = dir.path().join(name);
+        fs::write(&path, contents).unwrap();
+        path.to_str().unwrap().to_string()
+    }
+
+    /// The genesis hash of a config holding the given --primordial-accounts-file inputs,
+    /// added the way --reproducible adds them.
+    fn reproducible_genesis_hash(files: &[&str]) -> Result<Hash, String> {
+        let mut accounts = vec![];
+        for file in files {
+            accounts.extend(read_genesis_accounts(file).unwrap().0);
+        }
+        sort_genesis_accounts(&mut accounts)?;
+        let mut genesis_config = GenesisConfig {
+            creation_time: 1_704_067_200,
+            ..GenesisConfig::default()
+        };
+        for (pubkey, account) in accounts {
+            genesis_config.add_account(pubkey, account);
+        }
+        Ok(genesis_config.hash())
+    }
+
+    #[test]
+    fn test_reproducible_ignores_entry_order() {
+        let dir = tempfile::tempdir().unwrap();
+        let [a, b, c] = [
+            Pubkey::new_unique(),
+            Pubkey::new_unique(),
+            Pubkey::new_unique(),
+        ];
+        let forward = write_file(&dir, "forward.csv", &format!("{a},1\n{b},2\n{c},3\n"));
+        let backward = write_file(&dir, "backward.csv", &format!("{c},3\n{b},2\n{a},1\n"));
+        assert_eq!(
+            reproducible_genesis_hash(&[&forward]).unwrap(),
+            reproducible_genesis_hash(&[&backward]).unwrap()
+        );
+
+        let first = write_file(&dir, "first.csv", &format!("{b},2\n"));
+        let second = write_file(&dir, "second.csv", &format!("{c},3\n{a},1\n"));
+        assert_eq!(
+            reproducible_genesis_hash(&[&first, &second]).unwrap(),
+            reproducible_genesis_hash(&[&second, &first]).unwrap()
+        );
+        assert_eq!(
+            reproducible_genesis_hash(&[&first, &second]).unwrap(),
+            reproducible_genesis_hash(&[&forward]).unwrap()
+        );
+    }
+
+    #[test]
+    fn test_reproducible_rejects_pubkey_listed_twice() {
+        let dir = tempfile::tempdir().unwrap();
+        let pubkey = Pubkey::new_unique();
+        let first = write_file(&dir, "first.csv", &format!("{pubkey},1\n"));
+        let second = write_file(&dir, "second.csv", &format!("{pubkey},2\n"));
+        let err = reproducible_genesis_hash(&[&first, &second]).unwrap_err();
+        assert!(err.contains(&pubkey.to_string()), "{err}");
+    }
+
+    #[test]
+    fn test_accounts_schema_rejects_disallowed_owner() {
+        let dir = tempfile::tempdir().unwrap();
+        let schema = write_file(
+            &dir,
+            "schema.json",
+            &format!(
+                r#"{{"allowed_owners": ["{}", "{}"]}}"#,
+                system_program::id(),
+                solana_sdk_ids::vote::id()
+            ),
+        );
+        let schema = load_accounts_schema(&schema).unwrap();
+
+        let [allowed, disallowed, owner] = [
+            Pubkey::new_unique(),
+            Pubkey::new_unique(),
+            Pubkey::new_unique(),
+        ];
+        let primordial = write_file(
+            &dir,
+            "primordial.csv",
+            &format!("{allowed},1\n{disallowed},1,{owner}\n"),
+        );
+        let (accounts, _) = read_genesis_accounts(&primordial).unwrap();
+        let violations = accounts
+            .iter()
+            .flat_map(|(pubkey, account)| {
+                account_schema_violations(&schema, &primordial, pubkey, account)
+            })
+            .collect::<Vec<_>>();
+        assert_eq!(
+            violations,
+            [format!(
+                "{primordial}: account {disallowed} is owned by {owner}, which is not an allowed \
+                 owner"
+            )]
+        );
+
+        let [identity, vote, stake] = [
+            Pubkey::new_unique(),
+            Pubkey::new_unique(),
+            Pubkey::new_unique(),
+        ];
+        let validators = write_file(
+            &dir,
+            "validators.yml",
+            &format!(
+                "validator_accounts:\n  - identity_account: {identity}\n    vote_account: \
+                 {vote}\n    stake_account: {stake}\n    balance_lamports: 1000000000\n    \
+                 stake_lamports: 1000000000\n"
+            ),
+        );
+        let (accounts, violations) =
+            read_validator_accounts(&validators, 100, &Rent::default(), Some(&schema)).unwrap();
+        assert_eq!(accounts.len(), 3);
+        assert_eq!(
+            violations,
+            [format!(
+                "{validators}: validator #1: account {stake} is owned by {}, which is not an \
+                 allowed owner",
+                stake::id()
+            )]
+        );
+    }
+
+    #[test]
+    fn test_check_genesis_archive_unpacked_size() {
+        let genesis_config = GenesisConfig::default();
+        let genesis_size = bincode::serialized_size(&genesis_config).unwrap();
+        let min_size = genesis_size + GENESIS_ARCHIVE_LEDGER_MARGIN;
+
+        let err = check_genesis_archive_unpacked_size(&genesis_config, 1).unwrap_err();
+        assert_eq!(
+            err,
+            format!(
+                "--max-genesis-archive-unpacked-size 1 is too small, the minimum is {min_size}: \
+                 {genesis_size} bytes of serialized genesis config and \
+                 {GENESIS_ARCHIVE_LEDGER_MARGIN} for the ledger archived with it"
+            )
+        );
+        assert!(check_genesis_archive_unpacked_size(&genesis_config, min_size - 1).is_err());
+        assert!(check_genesis_archive_unpacked_size(&genesis_config, min_size).is_ok());
+        assert!(
+            check_genesis_archive_unpacked_size(&genesis_config, MAX_GENESIS_ARCHIVE_UNPACKED_SIZE)
+                .is_ok()
+        );
+    }
+
+    #[test]
+    fn test_parse_hashes_per_tick() {
+        let tick = Duration::from_micros(6250);
+        for cluster_type in [ClusterType::Development, ClusterType::MainnetBeta] {
+            assert_eq!(
+                parse_hashes_per_tick("12500", cluster_type, tick, true),
+                Ok(Some(12500))
+            );
+            assert_eq!(
+                parse_hashes_per_tick("sleep", cluster_type, tick, true),
+                Ok(None)
+            );
+            assert!(parse_hashes_per_tick("auto", cluster_type, tick, true).is_err());
+        }
+        assert_eq!(
+            parse_hashes_per_tick("auto", ClusterType::MainnetBeta, tick, false),
+            Ok(Some(clock::DEFAULT_HASHES_PER_TICK))
+        );
+        assert!(parse_hashes_per_tick("fast", ClusterType::MainnetBeta, tick, false).is_err());
+    }
+
+    #[test]
+    fn test_timing_rates() {
+        let genesis_config = GenesisConfig {
+            ticks_per_slot: 64,
+            poh_config: PohConfig {
+                target_tick_duration: Duration::from_micros(6250),
+                ..PohConfig::default()
+            },
+            epoch_schedule: EpochSchedule::custom(432_000, 432_000, false),
+            ..GenesisConfig::default()
+        };
+        assert!((ticks_per_second(&genesis_config) - 160.0).abs() < 1e-9);
+        assert!((slots_per_second(&genesis_config) - 2.5).abs() < 1e-9);
+        let slots_per_year = genesis_config.slots_per_year();
+        assert!((slots_per_year - 2.5 * 365.242_199 * 24.0 * 60.0 * 60.0).abs() < 1e-3);
+        assert!((epochs_per_year(&genesis_config) - slots_per_year / 432_000.0).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_canonical_dump_is_sorted_and_diffable() {
+        let pubkey = Pubkey::new_unique();
+        let mut genesis_config = GenesisConfig {
+            creation_time: 1_704_067_200,
+            ..GenesisConfig::default()
+        };
+        genesis_config.add_account(pubkey, AccountSharedData::new(42, 3, &system_program::id()));
+        let dump = canonical_dump(&genesis_config);
+        let lines = dump.lines().collect::<Vec<_>>();
+        let mut sorted_lines = lines.clone();
+        sorted_lines.sort_unstable();
+        assert_eq!(lines, sorted_lines);
+        for line in [
+            "creation_time = 1704067200".to_string(),
+            format!("accounts.{pubkey}.lamports = 42"),
+            format!("accounts.{pubkey}.data_len = 3"),
+        ] {
+            assert!(lines.contains(&line.as_str()), "{dump}");
+        }
+
+        genesis_config.ticks_per_slot = 65;
+        let changed_dump = canonical_dump(&genesis_config);
+        let changed_lines = dump
+            .lines()
+            .zip(changed_dump.lines())
+            .filter(|(line, changed_line)| line != changed_line)
+            .collect::<Vec<_>>();
+        assert_eq!(
+            changed_lines,
+            [("ticks_per_slot = 64", "ticks_per_slot = 65")]
+        );
+    }
+
+    #[test]
+    fn test_deactivate_stake_at_genesis() {
+        let [identity, vote, stake] = [(); 3].map(|_| Pubkey::new_unique());
+        let accounts = validator_accounts(
+            &[[identity, vote, stake]],
+            LAMPORTS_PER_SOL,
+            LAMPORTS_PER_SOL,
+            100,
+            &Rent::default(),
+            None,
+        )
+        .unwrap();
+        let account = |pubkey| {
+            let (_, account) = accounts.iter().find(|(key, _)| *key == pubkey).unwrap();
+            Account::from(account.clone())
+        };
+        let delegation = |account: &Account| match bincode::deserialize(&account.data).unwrap() {
+            StakeStateV2::Stake(_, stake, _) => stake.delegation,
+            _ => panic!("stake account is not delegated"),
+        };
+
+        let mut stake_account = account(stake);
+        let active = delegation(&stake_account);
+        assert_eq!(active.deactivation_epoch, u64::MAX);
+        deactivate_stake_at_genesis(&mut stake_account).unwrap();
+        let deactivating = delegation(&stake_account);
+        assert_eq!(deactivating.deactivation_epoch, 0);
+        assert_eq!(deactivating.stake, active.stake);
+        assert_eq!(deactivating.voter_pubkey, vote);
+
+        assert!(deactivate_stake_at_genesis(&mut account(vote)).is_err());
+    }
+
+    #[test]
+    fn test_check_economics() {
+        let codes = |genesis_config: &GenesisConfig| {
+            check_economics(genesis_config)
+                .into_iter()
+                .map(|warning| warning.code)
+                .collect::<Vec<_>>()
+        };
+        let mut genesis_config = GenesisConfig::default();
+        add_validator_accounts(
+            &mut genesis_config,
+            &[[(); 3].map(|_| Pubkey::new_unique())],
+            LAMPORTS_PER_SOL,
+            LAMPORTS_PER_SOL,
+            100,
+            &Rent::default(),
+            None,
+        )
+        .unwrap();
+        assert!(codes(&genesis_config).is_empty());
+
+        genesis_config.inflation = Inflation::new_disabled();
+        assert_eq!(codes(&genesis_config), ["inflation-disabled-with-rewards"]);
+
+        genesis_config.inflation = Inflation::default();
+        genesis_config.rent.lamports_per_byte_year = 0;
+        assert!(codes(&genesis_config).is_empty());
+        genesis_config.add_account(
+            Pubkey::new_unique(),
+            AccountSharedData::new(1, 0, &system_program::id()),
+        );
+        assert_eq!(
+            codes(&genesis_config),
+            ["rent-disabled-with-rent-paying-accounts"]
+        );
+    }
+
+    #[test]
+    fn test_read_bootstrap_validators_file() {
+        let dir = tempfile::tempdir().unwrap();
+        let [a, b, c, d, e, f] = [(); 6].map(|_| Pubkey::new_unique());
+        let file = write_file(
+            &dir,
+            "validators.txt",
+            &format!("# identity vote stake\n{a} {b}\t{c}\n\n  {d}, {e},{f}  \n"),
+        );
+        assert_eq!(
+            read_bootstrap_validators_file(&file),
+            Ok(vec![[a, b, c], [d, e, f]])
+        );
+
+        let file = write_file(&dir, "short.txt", &format!("{a} {b} {c}\n{d} {e}\n"));
+        assert_eq!(
+            read_bootstrap_validators_file(&file),
+            Err(
+                "line 2: expected IDENTITY_PUBKEY VOTE_PUBKEY STAKE_PUBKEY, but 2 values were \
+                 given"
+                    .to_string()
+            )
+        );
+        let file = write_file(&dir, "empty.csv", &format!("{a},,{c}\n"));
+        assert_eq!(
+            read_bootstrap_validators_file(&file),
+            Err("line 1: empty value".to_string())
+        );
+        let file = write_file(&dir, "invalid.txt", &format!("{a} {b} {c}\n{d} {e} nope\n"));
+        assert!(
+            read_bootstrap_validators_file(&file)
+                .unwrap_err()
+                .starts_with("line 2: ")
+        );
+        let missing = dir.path().join("missing.txt");
+        assert!(
+            read_bootstrap_validators_file(missing.to_str().unwrap())
+                .unwrap_err()
+                .starts_with("unable to read: ")
+        );
+    }
+
+    #[test]
+    fn test_read_validator_accounts_commission() {
+        let dir = tempfile::tempdir().unwrap();
+        let validators = [(); 3].map(|_| [(); 3].map(|_| Pubkey::new_unique()));
+        let entry = |[identity, vote, stake]: [Pubkey; 3], commission: &str| {
+            format!(
+                "  - identity_account: {identity}\n    vote_account: {vote}\n    stake_account: \
+                 {stake}\n    balance_lamports: 1000000000\n    stake_lamports: 1000000000\n\
+                 {commission}"
+            )
+        };
+        let file = write_file(
+            &dir,
+            "validators.yml",
+            &format!(
+                "validator_accounts:\n{}{}{}",
+                entry(validators[0], "    commission: 5\n"),
+                entry(validators[1], "    commission: \"7%\"\n"),
+                entry(validators[2], ""),
+            ),
+        );
+        let (accounts, violations) =
+            read_validator_accounts(&file, 100, &Rent::default(), None).unwrap();
+        assert!(violations.is_empty());
+        let commissions = validators.map(|[_, vote, _]| {
+            let (_, account) = accounts.iter().find(|(pubkey, _)| *pubkey == vote).unwrap();
+            VoteStateV3::deserialize(account.data()).unwrap().commission
+        });
+        assert_eq!(commissions, [5, 7, 100]);
+
+        let file = write_file(
+            &dir,
+            "invalid.yml",
+            &format!(
+                "validator_accounts:\n{}",
+                entry(validators[0], "    commission: 101\n")
+            ),
+        );
+        assert!(read_validator_accounts(&file, 100, &Rent::default(), None).is_err());
+    }
+
+    #[test]
+    fn test_validate_ticks_per_epoch() {
+        assert_eq!(
+            validate_ticks_per_epoch(
+                clock::DEFAULT_TICKS_PER_SLOT,
+                MINIMUM_SLOTS_PER_EPOCH,
+                DEFAULT_MIN_TICKS_PER_EPOCH
+            ),
+            Ok(())
+        );
+        assert_eq!(
+            validate_ticks_per_epoch(2, 32, 128),
+            Err(
+                "--ticks-per-slot 2 and --slots-per-epoch 32 give 64 ticks per epoch, the \
+                 minimum is 128 (--min-ticks-per-epoch)"
+                    .to_string()
+            )
+        );
+        assert_eq!(
+            validate_ticks_per_epoch(64, MINIMUM_SLOTS_PER_EPOCH - 1, 0),
+            Err(format!(
+                "--slots-per-epoch {} is below the minimum of {MINIMUM_SLOTS_PER_EPOCH}",
+                MINIMUM_SLOTS_PER_EPOCH - 1
+            ))
+        );
+        assert_eq!(
+            validate_ticks_per_epoch(u64::MAX, u64::MAX, u64::MAX),
+            Ok(())
+        );
+    }
+
+    #[test]
+    fn test_add_spl_programs() {
+        let dir = tempfile::tempdir().unwrap();
+        let mut genesis_config = GenesisConfig::default();
+        assert_eq!(
+            add_spl_programs(&mut genesis_config, &dir.path().join("missing")),
+            Err("not a directory".to_string())
+        );
+        write_file(&dir, "spl_token.so", "\x7fELF spl_token.so");
+        assert_eq!(
+            add_spl_programs(&mut genesis_config, dir.path()),
+            Err("missing spl_token_2022.so, spl_associated_token_account.so".to_string())
+        );
+        assert!(genesis_config.accounts.is_empty());
+
+        for (_, file_name, _) in SPL_PROGRAMS {
+            write_file(&dir, file_name, &format!("\x7fELF {file_name}"));
+        }
+        add_spl_programs(&mut genesis_config, dir.path()).unwrap();
+        assert_eq!(genesis_config.accounts.len(), 2 * SPL_PROGRAMS.len());
+        for (program_id, file_name, _) in SPL_PROGRAMS {
+            let program = &genesis_config.accounts[&program_id];
+            assert!(program.executable);
+            assert_eq!(program.owner, bpf_loader_upgradeable::id());
+            let UpgradeableLoaderState::Program {
+                programdata_address,
+            } = bincode::deserialize(&program.data).unwrap()
+            else {
+                panic!("{program_id} is not a program account");
+            };
+            let programdata = &genesis_config.accounts[&programdata_address];
+            assert_eq!(
+                programdata.data[UpgradeableLoaderState::size_of_programdata_metadata()..],
+                *format!("\x7fELF {file_name}").as_bytes()
+            );
+            assert_eq!(
+                programdata.lamports,
+                genesis_config.rent.minimum_balance(programdata.data.len())
+            );
+        }
+    }
+
+    #[test]
+    fn test_feature_activations() {
+        let feature_id = Pubkey::new_unique();
+        assert_eq!(
+            parse_feature_activation(&format!("{feature_id}:42")),
+            Ok((feature_id, 42))
+        );
+        assert!(parse_feature_activation(&feature_id.to_string()).is_err());
+        assert!(parse_feature_activation(&format!("{feature_id}:soon")).is_err());
+        assert!(parse_feature_activation("nope:42").is_err());
+
+        let mut genesis_config = GenesisConfig::default();
+        assert_eq!(
+            add_feature_accounts(&mut genesis_config, &[(feature_id, 1), (feature_id, 2)]),
+            Err(format!("feature {feature_id} is listed more than once"))
+        );
+        assert!(genesis_config.accounts.is_empty());
+        add_feature_accounts(&mut genesis_config, &[(feature_id, 42)]).unwrap();
+        assert_eq!(
+            feature::from_account(&genesis_config.accounts[&feature_id]),
+            Some(Feature {
+                activated_at: Some(42)
+            })
+        );
+    }
+
+    #[test]
+    fn test_read_genesis_accounts_yaml_and_csv() {
+        let dir = tempfile::tempdir().unwrap();
+        let [a, b, owner] = [(); 3].map(|_| Pubkey::new_unique());
+        let yaml = write_file(
+            &dir,
+            "accounts.yml",
+            &format!(
+                "{a}:\n  balance: 10\n{b}:\n  balance: 20\n  owner: {owner}\n  data: AQID\n  \
+                 executable: true\n"
+            ),
+        );
+        let csv = write_file(
+            &dir,
+            "accounts.csv",
+            &format!("pubkey,balance,owner\n# comment\n\n{a},10\n{b}, 20, {owner}\n"),
+        );
+        let (yaml_accounts, yaml_lamports) = read_genesis_accounts(&yaml).unwrap();
+        let (csv_accounts, csv_lamports) = read_genesis_accounts(&csv).unwrap();
+        assert_eq!((yaml_lamports, csv_lamports), (30, 30));
+        let account = |accounts: &[(Pubkey, AccountSharedData)], pubkey| {
+            assert_eq!(accounts.len(), 2);
+            let (_, account) = accounts.iter().find(|(key, _)| *key == pubkey).unwrap();
+            account.clone()
+        };
+        let system_account = AccountSharedData::new(10, 0, &system_program::id());
+        assert_eq!(account(&yaml_accounts, a), system_account);
+        assert_eq!(account(&csv_accounts, a), system_account);
+        let yaml_account = account(&yaml_accounts, b);
+        assert_eq!(
+            (
+                yaml_account.lamports(),
+                yaml_account.owner(),
+                yaml_account.data(),
+                yaml_account.executable()
+            ),
+            (20, &owner, &[1, 2, 3][..], true)
+        );
+        assert_eq!(
+            account(&csv_accounts, b),
+            AccountSharedData::new(20, 0, &owner)
+        );
+
+        for (name, contents, err) in [
+            (
+                "duplicate.csv",
+                format!("{a},1\n{a},2\n"),
+                format!("line 2: {a} is listed more than once"),
+            ),
+            (
+                "columns.csv",
+                format!("{a},1,{owner},4\n"),
+                "line 1: expected pubkey,balance[,owner], but 4 values were given".to_string(),
+            ),
+        ] {
+            let file = write_file(&dir, name, &contents);
+            assert_eq!(
+                read_genesis_accounts(&file).unwrap_err().to_string(),
+                format!("{file}: {err}")
+            );
+        }
+        let file = write_file(&dir, "balance.csv", &format!("{a},lots\n"));
+        let err = read_genesis_accounts(&file).unwrap_err().to_string();
+        assert!(err.contains("line 1: invalid balance lots"), "{err}");
+    }
+
+    #[test]
+    fn test_issued_lamports() {
+        let mut genesis_config = GenesisConfig::default();
+        assert_eq!(issued_lamports(&genesis_config), Ok(0));
+        genesis_config.add_account(
+            Pubkey::new_unique(),
+            AccountSharedData::new(u64::MAX - 1, 0, &system_program::id()),
+        );
+        genesis_config.add_account(
+            Pubkey::new_unique(),
+            AccountSharedData::new(1, 0, &system_program::id()),
+        );
+        assert_eq!(issued_lamports(&genesis_config), Ok(u64::MAX));
+        genesis_config.add_account(
+            Pubkey::new_unique(),
+            AccountSharedData::new(1, 0, &system_program::id()),
+        );
+        assert!(issued_lamports(&genesis_config).is_err());
+    }
+
+    #[test]
+    fn test_check_faucet_and_validator_lamports() {
+        assert_eq!(
+            check_faucet_and_validator_lamports(100, 2, 10, 20, Some(160)),
+            Ok(())
+        );
+        assert_eq!(
+            check_faucet_and_validator_lamports(100, 2, 10, 20, Some(159)),
+            Err(
+                "the faucet and bootstrap validators hold 160 lamports, more than \
+                 --max-faucet-and-validator-lamports 159"
+                    .to_string()
+            )
+        );
+        assert_eq!(
+            check_faucet_and_validator_lamports(u64::MAX, 0, u64::MAX, 0, None),
+            Ok(())
+        );
+        for (faucet_lamports, validator_count, validator_lamports, stake_lamports) in [
+            (u64::MAX, 1, 1, 0),
+            (0, 2, u64::MAX / 2 + 1, 0),
+            (0, 1, u64::MAX, 1),
+        ] {
+            assert!(
+                check_faucet_and_validator_lamports(
+                    faucet_lamports,
+                    validator_count,
+                    validator_lamports,
+                    stake_lamports,
+                    None
+                )
+                .is_err()
+            );
+        }
+    }
+
+    #[test]
+    fn test_check_hashes_per_tick() {
+        for hashes_per_tick in [None, Some(1)] {
+            assert!(
+                check_hashes_per_tick(hashes_per_tick, ClusterType::Development)
+                    .unwrap()
+                    .is_none()
+            );
+        }
+        for cluster_type in [
+            ClusterType::Devnet,
+            ClusterType::Testnet,
+            ClusterType::MainnetBeta,
+        ] {
+            let err = check_hashes_per_tick(None, cluster_type).err().unwrap();
+            assert!(err.contains(cluster_type_name(cluster_type)), "{err}");
+            let warning =
+                check_hashes_per_tick(Some(clock::DEFAULT_HASHES_PER_TICK - 1), cluster_type)
+                    .unwrap()
+                    .unwrap();
+            assert_eq!(warning.code, "hashes-per-tick-low");
+            assert!(
+                check_hashes_per_tick(Some(clock::DEFAULT_HASHES_PER_TICK), cluster_type)
+                    .unwrap()
+                    .is_none()
+            );
+        }
+    }
+
+    #[test]
+    fn test_account_data_sizes() {
+        let mut genesis_config = GenesisConfig::default();
+        let [small, large] = [(); 2].map(|_| Pubkey::new_unique());
+        genesis_config.add_account(small, AccountSharedData::new(1, 10, &system_program::id()));
+        genesis_config.add_account(
+            large,
+            AccountSharedData::new(1, MAX_PERMITTED_DATA_LENGTH as usize, &system_program::id()),
+        );
+        assert_eq!(validate_account_data_sizes(&genesis_config), Ok(()));
+        let largest = largest_accounts_by_data_size(&genesis_config, 1)
+            .into_iter()
+            .map(|(pubkey, _)| *pubkey)
+            .collect::<Vec<_>>();
+        assert_eq!(largest, [large]);
+        assert_eq!(largest_accounts_by_data_size(&genesis_config, 5).len(), 2);
+
+        let oversized = Pubkey::new_unique();
+        genesis_config.add_account(
+            oversized,
+            AccountSharedData::new(
+                1,
+                MAX_PERMITTED_DATA_LENGTH as usize + 1,
+                &system_program::id(),
+            ),
+        );
+        assert_eq!(
+            validate_account_data_sizes(&genesis_config),
+            Err(format!(
+                "account {oversized} has {} bytes of data, the maximum is \
+                 {MAX_PERMITTED_DATA_LENGTH}",
+                MAX_PERMITTED_DATA_LENGTH + 1
+            ))
+        );
+    }
+
+    #[test]
+    fn test_validate_bootstrap_validators() {
+        let [a, b, c, d] = [(); 4].map(|_| Pubkey::new_unique());
+        assert_eq!(
+            validate_bootstrap_validators(&[[a, b, c], [b, c, d]]),
+            Ok(())
+        );
+        assert_eq!(
+            validate_bootstrap_validators(&[[a, b, c], [d, a, d]]),
+            Err(format!(
+                "validator #2 reuses a pubkey across roles: identity {d}, vote {a}, stake {d}"
+            ))
+        );
+    }
+
+    #[test]
+    fn test_read_genesis_hash() {
+        let dir = tempfile::tempdir().unwrap();
+        let genesis_config = GenesisConfig::default();
+        genesis_config.write(dir.path()).unwrap();
+        assert_eq!(read_genesis_hash(dir.path()), Ok(genesis_config.hash()));
+
+        let missing = dir.path().join("missing");
+        assert!(
+            read_genesis_hash(&missing)
+                .unwrap_err()
+                .starts_with(&format!(
+                    "failed to read the genesis config back from {}: ",
+                    missing.display()
+                ))
+        );
+    }
+
+    #[test]
+    fn test_accounts_schema_required_fields_and_max_data_len() {
+        let dir = tempfile::tempdir().unwrap();
+        let schema = write_file(
+            &dir,
+            "schema.json",
+            r#"{"required_fields": ["commission"], "max_data_len": 4}"#,
+        );
+        let schema = load_accounts_schema(&schema).unwrap();
+
+        let [small, large] = [(); 2].map(|_| Pubkey::new_unique());
+        let primordial = write_file(
+            &dir,
+            "primordial.yml",
+            &format!(
+                "{small}:\n  balance: 1\n  data: AQID\n{large}:\n  balance: 1\n  data: AQIDBAU=\n"
+            ),
+        );
+        let (accounts, _) = read_genesis_accounts(&primordial).unwrap();
+        let violations = accounts
+            .iter()
+            .flat_map(|(pubkey, account)| {
+                account_schema_violations(&schema, &primordial, pubkey, account)
+            })
+            .collect::<Vec<_>>();
+        assert_eq!(
+            violations,
+            [format!(
+                "{primordial}: account {large} has 5 bytes of data, more than the max_data_len of \
+                 4"
+            )]
+        );
+
+        let [identity, vote, stake] = [(); 3].map(|_| Pubkey::new_unique());
+        let validators = write_file(
+            &dir,
+            "validators.yml",
+            &format!(
+                "validator_accounts:\n  - identity_account: {identity}\n    vote_account: \
+                 {vote}\n    stake_account: {stake}\n    balance_lamports: 1000000000\n    \
+                 stake_lamports: 1000000000\n"
+            ),
+        );
+        let (_, violations) =
+            read_validator_accounts(&validators, 100, &Rent::default(), Some(&schema)).unwrap();
+        // The vote and stake accounts hold more than 4 bytes of data as well.
+        assert_eq!(violations.len(), 3);
+        assert_eq!(
+            violations[0],
+            format!("{validators}: validator #1: required field 'commission' is missing")
+        );
+
+        let unknown_field = write_file(&dir, "unknown.json", r#"{"max_data_size": 4}"#);
+        assert!(load_accounts_schema(&unknown_field).is_err());
+    }
+
+    #[test]
+    fn test_check_faucet_lamports() {
+        let rent = Rent::default();
+        let minimum = rent.minimum_balance(0);
+        assert_eq!(
+            check_faucet_lamports(minimum - 1, &rent, ClusterType::Development).err(),
+            Some(format!(
+                "--faucet-lamports {} is below {minimum}, the rent exempt minimum of the faucet \
+                 account",
+                minimum - 1
+            ))
+        );
+        assert!(
+            check_faucet_lamports(minimum, &rent, ClusterType::Development)
+                .unwrap()
+                .is_none()
+        );
+        let warning = check_faucet_lamports(minimum, &rent, ClusterType::Testnet)
+            .unwrap()
+            .unwrap();
+        assert_eq!(warning.code, "faucet-underfunded");
+        assert!(warning.message.contains("testnet"), "{}", warning.message);
+        assert!(
+            check_faucet_lamports(1_000 * LAMPORTS_PER_SOL, &rent, ClusterType::MainnetBeta)
+                .unwrap()
+                .is_none()
+        );
+    }
+
+    #[test]
+    fn test_rent_paying_accounts() {
+        let mut genesis_config = GenesisConfig::default();
+        let [exempt, paying, allowed] = [(); 3].map(|_| Pubkey::new_unique());
+        let minimum = genesis_config.rent.minimum_balance(8);
+        for (pubkey, lamports) in [(exempt, minimum), (paying, minimum - 1), (allowed, 1)] {
+            genesis_config.add_account(
+                pubkey,
+                AccountSharedData::new(lamports, 8, &system_program::id()),
+            );
+        }
+        assert_eq!(
+            rent_paying_accounts(&genesis_config, &HashSet::from([allowed])),
+            [(paying, minimum - 1, minimum)]
+        );
+    }
+}
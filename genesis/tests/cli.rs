@@ -0,0 +1,314 @@
+use solana_genesis_config::{DEFAULT_GENESIS_FILE, GenesisConfig};
+use solana_hash::Hash;
+use solana_pubkey::Pubkey;
+use solana_shred_version::compute_shred_version;
+use std::fs;
+use std::process::Command;
+
+const FAUCET: Pubkey = Pubkey::new_from_array([1; 32]);
+const VALIDATOR: [Pubkey; 3] = [
+    Pubkey::new_from_array([2; 32]),
+    Pubkey::new_from_array([3; 32]),
+    Pubkey::new_from_array([4; 32]),
+];
+
+/// A genesis command for a `cluster_type` config holding a faucet and one bootstrap validator,
+/// created at a fixed time so that its genesis hash only depends on the arguments added to it.
+/// Development clusters sleep between ticks rather than measuring the hash rate of this
+/// computer, which also keeps their ledgers quick to create.
+fn genesis_command(cluster_type: &str) -> Command {
+    let mut command = Command::new(env!("CARGO_BIN_EXE_solarium-genesis"));
+    command
+        .args(["--cluster-type", cluster_type])
+        .args(["--creation-time", "2024-01-01T00:00:00Z"])
+        .args(["--faucet-pubkey", &FAUCET.to_string()])
+        .args(["--faucet-lamports", "1000000000000"])
+        .arg("--bootstrap-validator")
+        .args(VALIDATOR.map(|pubkey| pubkey.to_string()));
+    if cluster_type == "development" {
+        command.args(["--hashes-per-tick", "sleep"]);
+    }
+    command
+}
+
+/// Runs `command`, failing unless it succeeds, and returns its stdout.
+fn run(command: &mut Command) -> String {
+    let output = command.output().unwrap();
+    assert!(
+        output.status.success(),
+        "{}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+    String::from_utf8(output.stdout).unwrap()
+}
+
+/// Runs `command`, failing unless it exits with an error, and returns its stderr.
+fn run_failing(command: &mut Command) -> String {
+    let output = command.output().unwrap();
+    assert!(
+        !output.status.success(),
+        "{}",
+        String::from_utf8_lossy(&output.stdout)
+    );
+    String::from_utf8(output.stderr).unwrap()
+}
+
+/// The --canonical-dump written by `command` in --dry-run mode.
+fn canonical_dump(command: &mut Command) -> String {
+    let dir = tempfile::tempdir().unwrap();
+    let path = dir.path().join("dump.txt");
+    run(command.arg("--dry-run").arg("--canonical-dump").arg(&path));
+    fs::read_to_string(path).unwrap()
+}
+
+/// The value of the `name: value` line of `stdout`.
+fn output_value<'a>(stdout: &'a str, name: &str) -> &'a str {
+    stdout
+        .lines()
+        .find_map(|line| line.strip_prefix(name)?.strip_prefix(": "))
+        .unwrap_or_else(|| panic!("no {name} line in {stdout}"))
+}
+
+/// A genesis command running the subcommand `name`.
+fn genesis_subcommand(name: &str) -> Command {
+    let mut command = Command::new(env!("CARGO_BIN_EXE_solarium-genesis"));
+    command.arg(name);
+    command
+}
+
+#[test]
+fn test_dry_run_creates_no_ledger() {
+    let dir = tempfile::tempdir().unwrap();
+    let ledger_path = dir.path().join("ledger");
+    run(genesis_command("development")
+        .arg("--ledger")
+        .arg(&ledger_path)
+        .arg("--dry-run"));
+    assert!(!ledger_path.exists());
+
+    run(genesis_command("development")
+        .arg("--ledger")
+        .arg(&ledger_path));
+    assert!(ledger_path.join(DEFAULT_GENESIS_FILE).is_file());
+}
+
+#[test]
+fn test_rocksdb_compression() {
+    let dir = tempfile::tempdir().unwrap();
+    for compression in ["none", "snappy", "lz4", "zlib"] {
+        let ledger_path = dir.path().join(compression);
+        run(genesis_command("development")
+            .arg("--ledger")
+            .arg(&ledger_path)
+            .args(["--rocksdb-compression", compression]));
+        assert!(ledger_path.join(DEFAULT_GENESIS_FILE).is_file());
+    }
+
+    let stderr = run_failing(
+        genesis_command("development")
+            .arg("--dry-run")
+            .args(["--rocksdb-compression", "gzip"]),
+    );
+    assert!(stderr.contains("gzip"), "{stderr}");
+}
+
+#[test]
+fn test_warmup_epochs_default_to_cluster_type() {
+    for (dump, warmup) in [
+        (canonical_dump(&mut genesis_command("development")), true),
+        (
+            canonical_dump(genesis_command("development").arg("--disable-warmup-epochs")),
+            false,
+        ),
+        (canonical_dump(&mut genesis_command("mainnet-beta")), false),
+        (
+            canonical_dump(genesis_command("mainnet-beta").arg("--enable-warmup-epochs")),
+            true,
+        ),
+    ] {
+        assert!(
+            dump.contains(&format!("\nepoch_schedule.warmup = {warmup}\n")),
+            "{dump}"
+        );
+    }
+}
+
+#[test]
+fn test_prints_genesis_hash_and_shred_version() {
+    let dir = tempfile::tempdir().unwrap();
+    let ledger_path = dir.path().join("ledger");
+    let stdout = run(genesis_command("development")
+        .arg("--ledger")
+        .arg(&ledger_path));
+    let genesis_hash = GenesisConfig::load(&ledger_path).unwrap().hash();
+    assert_eq!(
+        output_value(&stdout, "Genesis hash"),
+        genesis_hash.to_string()
+    );
+    assert_eq!(
+        output_value(&stdout, "Shred version"),
+        compute_shred_version(&genesis_hash, None).to_string()
+    );
+
+    let stdout = run(genesis_command("development").arg("--skip-ledger-write"));
+    assert_eq!(
+        output_value(&stdout, "Genesis hash"),
+        genesis_hash.to_string()
+    );
+}
+
+#[test]
+fn test_expected_genesis_hash() {
+    let stdout = run(genesis_command("development").arg("--skip-ledger-write"));
+    let genesis_hash = output_value(&stdout, "Genesis hash");
+    run(genesis_command("development")
+        .arg("--skip-ledger-write")
+        .args(["--expected-genesis-hash", genesis_hash]));
+
+    let dir = tempfile::tempdir().unwrap();
+    let output = genesis_command("development")
+        .arg("--ledger")
+        .arg(dir.path().join("ledger"))
+        .args(["--expected-genesis-hash", &Hash::new_unique().to_string()])
+        .output()
+        .unwrap();
+    assert_eq!(output.status.code(), Some(1));
+    let stderr = String::from_utf8(output.stderr).unwrap();
+    assert!(stderr.contains("genesis hash mismatch"), "{stderr}");
+}
+
+#[test]
+fn test_burn_all_and_burn_none() {
+    for (flag, burn_percent) in [("--burn-all", 100), ("--burn-none", 0)] {
+        let dump = canonical_dump(genesis_command("development").arg(flag));
+        for key in ["rent.burn_percent", "fee_rate_governor.burn_percent"] {
+            assert!(
+                dump.contains(&format!("\n{key} = {burn_percent}\n")),
+                "{dump}"
+            );
+        }
+    }
+
+    let stderr = run_failing(genesis_command("development").arg("--dry-run").args([
+        "--burn-all",
+        "--rent-burn-percentage",
+        "50",
+    ]));
+    assert!(stderr.contains("cannot be used with"), "{stderr}");
+}
+
+#[test]
+fn test_reserve_lamports() {
+    let reserve = "GenesisReserve11111111111111111111111111111";
+    let dump =
+        canonical_dump(genesis_command("development").args(["--reserve-lamports", "5000000000"]));
+    assert!(
+        dump.contains(&format!("\naccounts.{reserve}.lamports = 5000000000\n")),
+        "{dump}"
+    );
+    assert!(
+        dump.contains(&format!(
+            "\naccounts.{reserve}.owner = 11111111111111111111111111111111\n"
+        )),
+        "{dump}"
+    );
+    assert!(!canonical_dump(&mut genesis_command("mainnet-beta")).contains(reserve));
+}
+
+#[test]
+fn test_verify() {
+    let dir = tempfile::tempdir().unwrap();
+    let ledger_path = dir.path().join("ledger");
+    let created = run(genesis_command("development")
+        .arg("--ledger")
+        .arg(&ledger_path));
+    let verified = run(genesis_subcommand("verify")
+        .arg("--ledger")
+        .arg(&ledger_path));
+    for name in ["Genesis hash", "Shred version"] {
+        assert_eq!(output_value(&verified, name), output_value(&created, name));
+    }
+    assert!(verified.contains("Issued lamports: "), "{verified}");
+
+    let stderr = run_failing(genesis_subcommand("verify").arg("--ledger").arg(dir.path()));
+    assert!(stderr.contains("has no genesis config"), "{stderr}");
+}
+
+#[test]
+fn test_activate_all_features() {
+    let feature_accounts = |dump: &str| {
+        dump.lines()
+            .filter(|line| line.ends_with(".owner = Feature111111111111111111111111111111111111"))
+            .count()
+    };
+    assert!(feature_accounts(&canonical_dump(&mut genesis_command("development"))) > 0);
+    assert_eq!(
+        feature_accounts(&canonical_dump(
+            genesis_command("development").arg("--deactivate-all-features")
+        )),
+        0
+    );
+    assert_eq!(
+        feature_accounts(&canonical_dump(&mut genesis_command("mainnet-beta"))),
+        0
+    );
+    assert!(
+        feature_accounts(&canonical_dump(
+            genesis_command("devnet").arg("--activate-all-features")
+        )) > 0
+    );
+
+    let stderr = run_failing(
+        genesis_command("mainnet-beta")
+            .arg("--dry-run")
+            .arg("--activate-all-features"),
+    );
+    assert!(
+        stderr.contains("--activate-all-features cannot be used with --cluster-type mainnet-beta"),
+        "{stderr}"
+    );
+}
+
+#[test]
+fn test_export_toml_import_round_trip() {
+    let dir = tempfile::tempdir().unwrap();
+    let ledger_path = dir.path().join("ledger");
+    let created = run(genesis_command("development")
+        .arg("--ledger")
+        .arg(&ledger_path));
+    let toml_path = dir.path().join("genesis.toml");
+    run(genesis_subcommand("export")
+        .arg("--ledger")
+        .arg(&ledger_path)
+        .args(["--output-format", "toml", "--output"])
+        .arg(&toml_path));
+    assert!(
+        fs::read_to_string(&toml_path)
+            .unwrap()
+            .contains("[accounts.")
+    );
+
+    let imported = run(genesis_subcommand("import")
+        .arg("--input")
+        .arg(&toml_path)
+        .arg("--ledger")
+        .arg(dir.path().join("imported")));
+    assert_eq!(
+        output_value(&imported, "Genesis hash"),
+        output_value(&created, "Genesis hash")
+    );
+}
+
+#[test]
+fn test_print_defaults() {
+    let stdout = run(genesis_subcommand("print-defaults").args(["--cluster-type", "testnet"]));
+    assert!(stdout.contains("Where the defaults come from:"), "{stdout}");
+    assert_eq!(
+        output_value(&stdout, "  Slots per epoch"),
+        "--cluster-type testnet"
+    );
+    assert_eq!(
+        output_value(&stdout, "  Capitalization"),
+        "--cluster-type testnet activates no features"
+    );
+}
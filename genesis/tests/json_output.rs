@@ -0,0 +1,42 @@
+use solana_pubkey::Pubkey;
+use std::fs;
+use std::process::Command;
+
+#[test]
+fn test_json_output_alongside_human_output() {
+    let dir = tempfile::tempdir().unwrap();
+    let json_path = dir.path().join("genesis.json");
+    let [identity, vote, stake, faucet] = [
+        Pubkey::new_unique(),
+        Pubkey::new_unique(),
+        Pubkey::new_unique(),
+        Pubkey::new_unique(),
+    ];
+
+    let output = Command::new(env!("CARGO_BIN_EXE_solarium-genesis"))
+        .args(["--faucet-pubkey", &faucet.to_string()])
+        .args(["--faucet-lamports", "1000000000000"])
+        .arg("--bootstrap-validator")
+        .args([identity, vote, stake].map(|pubkey| pubkey.to_string()))
+        .arg("--dry-run")
+        .arg("--json-output")
+        .arg(&json_path)
+        .output()
+        .unwrap();
+    assert!(
+        output.status.success(),
+        "{}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+    let stdout = String::from_utf8(output.stdout).unwrap();
+    assert!(stdout.contains("Issued lamports: "), "{stdout}");
+
+    let json: serde_json::Value =
+        serde_json::from_str(&fs::read_to_string(&json_path).unwrap()).unwrap();
+    assert!(json["genesis_hash"].is_string());
+    assert!(json["shred_version"].is_u64());
+    let accounts = json["config"]["accounts"].as_object().unwrap();
+    for pubkey in [identity, vote, stake, faucet] {
+        assert!(accounts.contains_key(&pubkey.to_string()), "{pubkey}");
+    }
+}
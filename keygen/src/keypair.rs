@@ -1,14 +1,812 @@
+use aes_gcm::aead::rand_core::RngCore;
+use aes_gcm::aead::{Aead, AeadCore, KeyInit, Nonce, OsRng};
+use aes_gcm::{Aes256Gcm, Key};
+use argon2::Argon2;
+use base64::Engine;
+use base64::prelude::BASE64_STANDARD;
 use rpassword::prompt_password;
+use serde::{Deserialize, Serialize};
+use solana_keypair::{KEYPAIR_LENGTH, Keypair, keypair_from_seed, read_keypair};
+use solana_pubkey::Pubkey;
+use solana_signature::Signature;
+use solana_signer::Signer;
 use std::error;
+use std::fs;
+use std::io::{self, BufRead, IsTerminal, Read, Write, stdin, stdout};
+use std::time::{SystemTime, UNIX_EPOCH};
+use zeroize::Zeroizing;
 
 /// Prompts user for a passphrase and then asks for confirmation to check for mistakes.
-pub(crate) fn prompt_passphrase(prompt: &str) -> Result<String, Box<dyn error::Error>> {
-    let passphrase = prompt_password(prompt)?;
+pub(crate) fn prompt_passphrase(prompt: &str) -> Result<Zeroizing<String>, Box<dyn error::Error>> {
+    let passphrase = Zeroizing::new(prompt_password(prompt)?);
     if !passphrase.is_empty() {
-        let confirmed = prompt_password("Enter same passphrase again: ")?;
+        let confirmed = Zeroizing::new(prompt_password("Enter same passphrase again: ")?);
         if confirmed != passphrase {
             return Err("Passphrases did not match".into());
         }
     }
     Ok(passphrase)
 }
+
+/// Prompts user for a keypair encryption password and then asks for confirmation. Unlike a
+/// BIP39 passphrase, the password cannot be empty.
+pub(crate) fn prompt_encryption_password() -> Result<Zeroizing<String>, Box<dyn error::Error>> {
+    let password = read_secret("Keypair encryption password: ")?;
+    if password.is_empty() {
+        return Err("Encryption password cannot be empty".into());
+    }
+    let confirmed = read_secret("Enter same password again: ")?;
+    if confirmed != password {
+        return Err("Passwords did not match".into());
+    }
+    Ok(password)
+}
+
+/// Reads a secret with a hidden prompt, or as a plain line when stdin is not a terminal so
+/// that it can be piped in. The secret is wiped from memory when the returned value is dropped.
+pub(crate) fn read_secret(prompt: &str) -> io::Result<Zeroizing<String>> {
+    if stdin().is_terminal() {
+        prompt_password(prompt).map(Zeroizing::new)
+    } else {
+        let mut line = Zeroizing::new(String::new());
+        stdin().read_line(&mut line)?;
+        let len = line.trim_end_matches(['\n', '\r']).len();
+        line.truncate(len);
+        Ok(line)
+    }
+}
+
+/// Prompts user for a seed phrase and collapses any extra whitespace between the words.
+pub(crate) fn prompt_seed_phrase(prompt: &str) -> Result<Zeroizing<String>, Box<dyn error::Error>> {
+    let seed_phrase = read_secret(prompt)?;
+    Ok(Zeroizing::new(
+        seed_phrase.split_whitespace().collect::<Vec<_>>().join(" "),
+    ))
+}
+
+/// Shows the recovered pubkey and asks user whether to continue.
+pub(crate) fn confirm_pubkey(pubkey: &Pubkey) -> Result<bool, Box<dyn error::Error>> {
+    print!("Recovered pubkey `{pubkey}`. Continue? (y/n): ");
+    stdout().flush()?;
+    let mut input = String::new();
+    stdin().read_line(&mut input)?;
+    Ok(input.trim().eq_ignore_ascii_case("y"))
+}
+
+// Clears the screen, wipes the scrollback buffer and moves the cursor home.
+const CLEAR_SCREEN_AND_SCROLLBACK: &str = "\x1b[2J\x1b[3J\x1b[H";
+
+/// Waits for user to confirm the seed phrase has been recorded and then clears the terminal,
+/// including its scrollback. Does nothing when `output` is not a terminal.
+pub(crate) fn confirm_and_clear_screen(
+    input: &mut impl BufRead,
+    output: &mut impl Write,
+    is_terminal: bool,
+) -> io::Result<()> {
+    if !is_terminal {
+        return Ok(());
+    }
+    write!(
+        output,
+        "Press ENTER once you have recorded the seed phrase to clear the screen: "
+    )?;
+    output.flush()?;
+    input.read_line(&mut String::new())?;
+    write!(output, "{CLEAR_SCREEN_AND_SCROLLBACK}")?;
+    output.flush()
+}
+
+// How many words of a new seed phrase new --confirm-seed-phrase asks to type back.
+const CONFIRM_SEED_PHRASE_WORDS: usize = 3;
+
+/// Asks for the words of `phrase` at a few random positions until all of them are typed back
+/// correctly, then clears the typed words away. Words are compared ignoring case and
+/// surrounding whitespace. Meant to follow [`confirm_and_clear_screen`].
+pub(crate) fn confirm_seed_phrase(
+    input: &mut impl BufRead,
+    output: &mut impl Write,
+    phrase: &str,
+) -> io::Result<()> {
+    let words = phrase.split_whitespace().collect::<Vec<_>>();
+    let mut positions = vec![];
+    while positions.len() < CONFIRM_SEED_PHRASE_WORDS.min(words.len()) {
+        let position = OsRng.next_u32() as usize % words.len();
+        if !positions.contains(&position) {
+            positions.push(position);
+        }
+    }
+    positions.sort_unstable();
+
+    writeln!(output, "Confirm the recorded seed phrase")?;
+    loop {
+        let mut matched = true;
+        for &position in &positions {
+            write!(output, "Word #{}: ", position + 1)?;
+            output.flush()?;
+            let mut word = Zeroizing::new(String::new());
+            if input.read_line(&mut word)? == 0 {
+                return Err(io::Error::new(
+                    io::ErrorKind::UnexpectedEof,
+                    "seed phrase confirmation was not finished",
+                ));
+            }
+            matched &= word.trim().to_lowercase() == words[position].to_lowercase();
+        }
+        if matched {
+            break;
+        }
+        writeln!(output, "The words do not match the seed phrase, try again")?;
+    }
+    write!(output, "{CLEAR_SCREEN_AND_SCROLLBACK}")?;
+    output.flush()
+}
+
+/// Moves the keypair file at `path` aside to `<path>.bak-<unix timestamp>` and returns the
+/// backup path. An existing backup is never replaced.
+pub(crate) fn backup_keypair_file(path: &str) -> Result<String, Box<dyn error::Error>> {
+    let timestamp = SystemTime::now().duration_since(UNIX_EPOCH)?.as_secs();
+    let backup_path = format!("{path}.bak-{timestamp}");
+    if fs::symlink_metadata(&backup_path).is_ok() {
+        return Err(format!("Unable to back up {path}, {backup_path} already exists").into());
+    }
+    fs::rename(path, &backup_path)
+        .map_err(|err| format!("Unable to back up {path} to {backup_path}: {err}"))?;
+    Ok(backup_path)
+}
+
+/// Reads a keypair in any supported format, prompting for the password of an encrypted one.
+/// When the keypair itself was read from stdin the password can only come from the terminal.
+pub(crate) fn read_keypair_any_format(
+    reader: &mut impl Read,
+    from_stdin: bool,
+) -> Result<Keypair, Box<dyn error::Error>> {
+    let mut buffer = Zeroizing::new(String::new());
+    reader.read_to_string(&mut buffer)?;
+    let trimmed = buffer.trim();
+    if trimmed.starts_with('[') {
+        read_keypair(&mut trimmed.as_bytes())
+    } else if trimmed.starts_with('{') {
+        let password = if from_stdin {
+            prompt_password("Keypair file password: ")
+                .map(Zeroizing::new)
+                .map_err(|err| {
+                    format!(
+                        "An encrypted keypair read from stdin needs a terminal for the \
+                         password: {err}"
+                    )
+                })?
+        } else {
+            read_secret("Keypair file password: ")?
+        };
+        decrypt_keypair(trimmed, &password)
+    } else {
+        let bytes = bs58::decode(trimmed)
+            .into_vec()
+            .map(Zeroizing::new)
+            .map_err(|err| format!("Input is neither a JSON array nor base58: {err}"))?;
+        if bytes.len() != KEYPAIR_LENGTH {
+            return Err(format!(
+                "Expected {KEYPAIR_LENGTH} base58 encoded bytes, found {}",
+                bytes.len()
+            )
+            .into());
+        }
+        Keypair::try_from(bytes.as_slice()).map_err(|err| format!("Invalid keypair: {err}").into())
+    }
+}
+
+/// Format name of an encrypted keypair file, as returned by `keypair_format_name`.
+pub(crate) const ENCRYPTED_KEYPAIR_FORMAT: &str = "encrypted";
+
+/// Names the format of keypair file contents the way `read_keypair_any_format` tells them
+/// apart, without decoding anything.
+pub(crate) fn keypair_format_name(contents: &str) -> &'static str {
+    match contents.trim_start().chars().next() {
+        Some('[') => "JSON array",
+        Some('{') => ENCRYPTED_KEYPAIR_FORMAT,
+        _ => "base58",
+    }
+}
+
+/// Signs a random message with `keypair` and checks the signature against `pubkey`, which
+/// also catches a keypair whose secret half is corrupted.
+pub(crate) fn verify_keypair(keypair: &Keypair, pubkey: &Pubkey) -> bool {
+    let mut message = [0u8; 32];
+    OsRng.fill_bytes(&mut message);
+    keypair
+        .sign_message(&message)
+        .verify(pubkey.as_ref(), &message)
+}
+
+/// Prefix of every message signed by sign-message and new --attest. No transaction message
+/// starts with 0xff, so a signed message can never be replayed as a transaction.
+pub(crate) const SIGNED_MESSAGE_DOMAIN: &[u8] = b"\xffsolarium signed message:\n";
+
+/// Signs `message` behind the [`SIGNED_MESSAGE_DOMAIN`] prefix.
+pub(crate) fn sign_domain_message(keypair: &Keypair, message: &[u8]) -> Signature {
+    keypair.sign_message(&[SIGNED_MESSAGE_DOMAIN, message].concat())
+}
+
+/// Checks a signature made by [`sign_domain_message`].
+pub(crate) fn verify_domain_message(
+    pubkey: &Pubkey,
+    message: &[u8],
+    signature: &Signature,
+) -> bool {
+    signature.verify(pubkey.as_ref(), &[SIGNED_MESSAGE_DOMAIN, message].concat())
+}
+
+/// Deterministic test identities and the hex ed25519 secret keys they are built from, the
+/// TEST 1, TEST 2, TEST 3 and TEST 1024 vectors of RFC 8032 section 7.1. Anyone can derive
+/// these keys, so they must never hold real funds.
+pub(crate) const FIXTURE_SECRET_KEYS: &[(&str, &str)] = &[
+    (
+        "alice",
+        "9d61b19deffd5a60ba844af492ec2cc44449c5697b326919703bac031cae7f60",
+    ),
+    (
+        "bob",
+        "4ccd089b28ff96da9db6c346ec114e0f5b8a319f35aba624da8cf6ed4fb8a6fb",
+    ),
+    (
+        "carol",
+        "c5aa8df43f9f837bedb7442f31dcb7b166d38535076f094b85ce3a2e0b4458f7",
+    ),
+    (
+        "dave",
+        "f5e5767cf153319517630f226876b86c8160cc583bc013744c6bf255f5cc0ee5",
+    ),
+];
+
+/// Returns the keypair of the fixture called `name`, if there is one.
+pub(crate) fn fixture_keypair(name: &str) -> Option<Keypair> {
+    FIXTURE_SECRET_KEYS
+        .iter()
+        .find(|(fixture, _)| *fixture == name)
+        .map(|(_, secret_key)| {
+            keypair_from_secret_hex(secret_key).expect("fixture secret keys are valid hex")
+        })
+}
+
+/// Builds a keypair from hex holding either the 64 keypair bytes or the 32 byte secret key.
+pub(crate) fn keypair_from_secret_hex(hex: &str) -> Result<Keypair, Box<dyn error::Error>> {
+    let bytes = Zeroizing::new(decode_hex(hex.trim())?);
+    match bytes.len() {
+        KEYPAIR_LENGTH => Keypair::try_from(bytes.as_slice())
+            .map_err(|err| format!("Invalid keypair: {err}").into()),
+        Keypair::SECRET_KEY_LENGTH => {
+            let secret_key = Zeroizing::new(<[u8; Keypair::SECRET_KEY_LENGTH]>::try_from(
+                bytes.as_slice(),
+            )?);
+            Ok(Keypair::new_from_array(*secret_key))
+        }
+        len => Err(format!(
+            "Expected {KEYPAIR_LENGTH} or {} hex encoded bytes, found {len}",
+            Keypair::SECRET_KEY_LENGTH
+        )
+        .into()),
+    }
+}
+
+// Lengths of a raw seed accepted by `keypair_from_raw_seed`: an ed25519 seed or a BIP39 seed
+const RAW_SEED_LENGTHS: [usize; 2] = [32, 64];
+
+/// Builds a keypair straight from a raw 32 or 64 byte seed in hex or base58, as
+/// `keypair_from_seed` does for the seed of a seed phrase.
+pub(crate) fn keypair_from_raw_seed(encoded: &str) -> Result<Keypair, Box<dyn error::Error>> {
+    let encoded = encoded.trim();
+    // Hex is tried first, as a hex string of a valid length never decodes to a valid length
+    // as base58.
+    let seed = Zeroizing::new(
+        decode_hex(encoded)
+            .ok()
+            .filter(|seed| RAW_SEED_LENGTHS.contains(&seed.len()))
+            .or_else(|| bs58::decode(encoded).into_vec().ok())
+            .ok_or("Seed is neither valid hex nor valid base58")?,
+    );
+    if !RAW_SEED_LENGTHS.contains(&seed.len()) {
+        return Err(format!(
+            "Expected a seed of {} or {} bytes, found {}",
+            RAW_SEED_LENGTHS[0],
+            RAW_SEED_LENGTHS[1],
+            seed.len()
+        )
+        .into());
+    }
+    keypair_from_seed(&seed)
+}
+
+const ENCRYPTION_SALT_LENGTH: usize = 16;
+
+// Version 1 envelopes derive the key with Argon2id and its default parameters, and were
+// written before the version field existed. Version 2 envelopes derive it with scrypt and
+// record the scrypt cost parameters. Only the latest version is written.
+const ARGON2_ENCRYPTED_KEYPAIR_VERSION: u32 = 1;
+const ENCRYPTED_KEYPAIR_VERSION: u32 = 2;
+
+// Highest scrypt cost accepted from an envelope, so that a crafted file cannot make keygen
+// allocate more than 1 GiB while deriving the key.
+const MAX_SCRYPT_LOG_N: u8 = 20;
+
+/// Envelope of an encrypted keypair file. The keypair bytes are encrypted with AES-256-GCM
+/// under a key derived from the password, see `ENCRYPTED_KEYPAIR_VERSION`.
+#[derive(Serialize, Deserialize)]
+struct EncryptedKeypair {
+    #[serde(default = "first_encrypted_keypair_version")]
+    version: u32,
+    encrypted: bool,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    scrypt: Option<ScryptParams>,
+    salt: String,
+    nonce: String,
+    ciphertext: String,
+}
+
+#[derive(Clone, Copy, Serialize, Deserialize)]
+struct ScryptParams {
+    log_n: u8,
+    r: u32,
+    p: u32,
+}
+
+impl Default for ScryptParams {
+    fn default() -> Self {
+        Self {
+            log_n: scrypt::Params::RECOMMENDED_LOG_N,
+            r: scrypt::Params::RECOMMENDED_R,
+            p: scrypt::Params::RECOMMENDED_P,
+        }
+    }
+}
+
+/// Encrypts the keypair with `password` and returns the JSON envelope to store.
+pub(crate) fn encrypt_keypair(
+    keypair: &Keypair,
+    password: &str,
+) -> Result<String, Box<dyn error::Error>> {
+    encrypt_keypair_with(keypair, password, ScryptParams::default())
+}
+
+fn encrypt_keypair_with(
+    keypair: &Keypair,
+    password: &str,
+    params: ScryptParams,
+) -> Result<String, Box<dyn error::Error>> {
+    let mut salt = [0u8; ENCRYPTION_SALT_LENGTH];
+    OsRng.fill_bytes(&mut salt);
+    let cipher = scrypt_cipher(password, &salt, params)?;
+    let nonce = Aes256Gcm::generate_nonce(&mut OsRng);
+    let ciphertext = cipher
+        .encrypt(&nonce, Zeroizing::new(keypair.to_bytes()).as_slice())
+        .map_err(|_| "Unable to encrypt keypair")?;
+    let envelope = EncryptedKeypair {
+        version: ENCRYPTED_KEYPAIR_VERSION,
+        encrypted: true,
+        scrypt: Some(params),
+        salt: encode_hex(&salt),
+        nonce: encode_hex(&nonce),
+        ciphertext: encode_hex(&ciphertext),
+    };
+    Ok(serde_json::to_string(&envelope)?)
+}
+
+/// Decrypts a keypair from the JSON envelope written by `encrypt_keypair`.
+pub(crate) fn decrypt_keypair(
+    envelope: &str,
+    password: &str,
+) -> Result<Keypair, Box<dyn error::Error>> {
+    let envelope: EncryptedKeypair = serde_json::from_str(envelope)
+        .map_err(|err| format!("Invalid encrypted keypair: {err}"))?;
+    if !envelope.encrypted {
+        return Err("Invalid encrypted keypair: not marked as encrypted".into());
+    }
+    let salt = decode_hex(&envelope.salt)?;
+    let cipher = match (envelope.version, envelope.scrypt) {
+        (ARGON2_ENCRYPTED_KEYPAIR_VERSION, None) => argon2_cipher(password, &salt)?,
+        (ENCRYPTED_KEYPAIR_VERSION, Some(params)) => scrypt_cipher(password, &salt, params)?,
+        (ENCRYPTED_KEYPAIR_VERSION, None) => {
+            return Err("Invalid encrypted keypair: missing scrypt parameters".into());
+        }
+        (version, _) => {
+            return Err(format!(
+                "Unsupported encrypted keypair version {version}, this keygen reads versions \
+                 {ARGON2_ENCRYPTED_KEYPAIR_VERSION} to {ENCRYPTED_KEYPAIR_VERSION}"
+            )
+            .into());
+        }
+    };
+    let nonce = decode_hex(&envelope.nonce)?;
+    if nonce.len() != Nonce::<Aes256Gcm>::default().len() {
+        return Err(format!(
+            "Invalid encrypted keypair: bad nonce length {}",
+            nonce.len()
+        )
+        .into());
+    }
+    let ciphertext = decode_hex(&envelope.ciphertext)?;
+    let bytes = Zeroizing::new(
+        cipher
+            .decrypt(
+                Nonce::<Aes256Gcm>::from_slice(&nonce),
+                ciphertext.as_slice(),
+            )
+            .map_err(|_| "Unable to decrypt keypair: wrong password or corrupted file")?,
+    );
+    Keypair::try_from(bytes.as_slice()).map_err(|err| format!("Invalid keypair: {err}").into())
+}
+
+fn first_encrypted_keypair_version() -> u32 {
+    1
+}
+
+fn argon2_cipher(password: &str, salt: &[u8]) -> Result<Aes256Gcm, Box<dyn error::Error>> {
+    let mut key = Zeroizing::new([0u8; 32]);
+    Argon2::default()
+        .hash_password_into(password.as_bytes(), salt, key.as_mut_slice())
+        .map_err(|err| format!("Unable to derive encryption key: {err}"))?;
+    Ok(Aes256Gcm::new(Key::<Aes256Gcm>::from_slice(key.as_slice())))
+}
+
+fn scrypt_cipher(
+    password: &str,
+    salt: &[u8],
+    params: ScryptParams,
+) -> Result<Aes256Gcm, Box<dyn error::Error>> {
+    if params.log_n > MAX_SCRYPT_LOG_N {
+        return Err(format!(
+            "Invalid encrypted keypair: scrypt log_n {} is above the maximum of \
+             {MAX_SCRYPT_LOG_N}",
+            params.log_n
+        )
+        .into());
+    }
+    let mut key = Zeroizing::new([0u8; 32]);
+    let params = scrypt::Params::new(params.log_n, params.r, params.p, key.len())
+        .map_err(|err| format!("Invalid encrypted keypair: bad scrypt parameters: {err}"))?;
+    scrypt::scrypt(password.as_bytes(), salt, &params, key.as_mut_slice())
+        .map_err(|err| format!("Unable to derive encryption key: {err}"))?;
+    Ok(Aes256Gcm::new(Key::<Aes256Gcm>::from_slice(key.as_slice())))
+}
+
+/// Encodes `pubkey` as base58, hex or base64, the values of the --encoding argument.
+pub(crate) fn encode_pubkey(pubkey: &Pubkey, encoding: &str) -> String {
+    match encoding {
+        "base58" => pubkey.to_string(),
+        "hex" => encode_hex(pubkey.as_ref()),
+        "base64" => BASE64_STANDARD.encode(pubkey),
+        _ => unreachable!(),
+    }
+}
+
+fn encode_hex(bytes: &[u8]) -> String {
+    bytes.iter().map(|byte| format!("{byte:02x}")).collect()
+}
+
+pub(crate) fn decode_hex(hex: &str) -> Result<Vec<u8>, String> {
+    let hex = hex.strip_prefix("0x").unwrap_or(hex);
+    if !hex.is_ascii() || !hex.len().is_multiple_of(2) {
+        return Err("Hex string must consist of an even number of hex digits".to_string());
+    }
+    hex.as_bytes()
+        .chunks(2)
+        .map(|pair| {
+            let pair = std::str::from_utf8(pair).unwrap();
+            // from_str_radix also takes a sign, which is no hex digit.
+            u8::from_str_radix(pair, 16)
+                .ok()
+                .filter(|_| pair.bytes().all(|digit| digit.is_ascii_hexdigit()))
+                .ok_or_else(|| format!("Invalid hex digits '{pair}'"))
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // Cheap scrypt parameters so the tests do not spend seconds deriving keys.
+    const TEST_PARAMS: ScryptParams = ScryptParams {
+        log_n: 4,
+        r: 8,
+        p: 1,
+    };
+
+    fn envelope_with(keypair: &Keypair, password: &str) -> serde_json::Value {
+        serde_json::from_str(&encrypt_keypair_with(keypair, password, TEST_PARAMS).unwrap())
+            .unwrap()
+    }
+
+    #[test]
+    fn test_confirm_and_clear_screen() {
+        let mut input = "\nleft over".as_bytes();
+        let mut output = vec![];
+        confirm_and_clear_screen(&mut input, &mut output, true).unwrap();
+        assert_eq!(
+            String::from_utf8(output).unwrap(),
+            format!(
+                "Press ENTER once you have recorded the seed phrase to clear the screen: \
+                 {CLEAR_SCREEN_AND_SCROLLBACK}"
+            )
+        );
+        assert_eq!(input, b"left over");
+
+        // Output that is not a terminal is neither prompted on nor cleared.
+        let mut input = "\n".as_bytes();
+        let mut output = vec![];
+        confirm_and_clear_screen(&mut input, &mut output, false).unwrap();
+        assert!(output.is_empty());
+        assert_eq!(input, b"\n");
+    }
+
+    #[test]
+    fn test_domain_message_signature() {
+        let keypair = Keypair::new();
+        let message = b"prove you own this address";
+        let signature = sign_domain_message(&keypair, message);
+        assert!(verify_domain_message(
+            &keypair.pubkey(),
+            message,
+            &signature
+        ));
+        assert!(!verify_domain_message(
+            &keypair.pubkey(),
+            b"another message",
+            &signature
+        ));
+        assert!(!verify_domain_message(
+            &Pubkey::new_unique(),
+            message,
+            &signature
+        ));
+        // A signature of the bare message, as over a transaction, does not verify.
+        let bare = keypair.sign_message(message);
+        assert!(!verify_domain_message(&keypair.pubkey(), message, &bare));
+    }
+
+    #[test]
+    fn test_fixture_keypair() {
+        // Public keys of the RFC 8032 section 7.1 test vectors.
+        for (name, public_key) in [
+            (
+                "alice",
+                "d75a980182b10ab7d54bfed3c964073a0ee172f3daa62325af021a68f707511a",
+            ),
+            (
+                "bob",
+                "3d4017c3e843895a92b70aa74d1b7ebc9c982ccf2ec4968cc0cd55f12af4660c",
+            ),
+            (
+                "carol",
+                "fc51cd8e6218a1a38da47ed00230f0580816ed13ba3303ac5deb911548908025",
+            ),
+            (
+                "dave",
+                "278117fc144c72340f67d0f2316e8386ceffbf2b2428c9c51fef7c597f1d426e",
+            ),
+        ] {
+            let keypair = fixture_keypair(name).unwrap();
+            assert_eq!(encode_hex(keypair.pubkey().as_ref()), public_key, "{name}");
+        }
+        assert!(fixture_keypair("eve").is_none());
+    }
+
+    #[test]
+    fn test_encrypted_keypair_round_trip() {
+        let keypair = Keypair::new();
+        let envelope = encrypt_keypair_with(&keypair, "secret", TEST_PARAMS).unwrap();
+        let decrypted = decrypt_keypair(&envelope, "secret").unwrap();
+        assert_eq!(decrypted.to_bytes(), keypair.to_bytes());
+        assert_eq!(keypair_format_name(&envelope), ENCRYPTED_KEYPAIR_FORMAT);
+    }
+
+    #[test]
+    fn test_encrypted_keypair_wrong_password() {
+        let envelope = encrypt_keypair_with(&Keypair::new(), "secret", TEST_PARAMS).unwrap();
+        let err = decrypt_keypair(&envelope, "Secret").unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "Unable to decrypt keypair: wrong password or corrupted file"
+        );
+    }
+
+    #[test]
+    fn test_encrypted_keypair_truncated() {
+        let envelope = encrypt_keypair_with(&Keypair::new(), "secret", TEST_PARAMS).unwrap();
+        let err = decrypt_keypair(&envelope[..envelope.len() / 2], "secret").unwrap_err();
+        assert!(err.to_string().starts_with("Invalid encrypted keypair: "));
+
+        let mut envelope = envelope_with(&Keypair::new(), "secret");
+        let ciphertext = envelope["ciphertext"].as_str().unwrap();
+        envelope["ciphertext"] = ciphertext[..ciphertext.len() - 2].into();
+        let err = decrypt_keypair(&envelope.to_string(), "secret").unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "Unable to decrypt keypair: wrong password or corrupted file"
+        );
+    }
+
+    #[test]
+    fn test_encrypted_keypair_versions() {
+        // Version 1 envelopes have no version field and use Argon2id.
+        let keypair = Keypair::new();
+        let salt = [7u8; ENCRYPTION_SALT_LENGTH];
+        let nonce = Aes256Gcm::generate_nonce(&mut OsRng);
+        let ciphertext = argon2_cipher("secret", &salt)
+            .unwrap()
+            .encrypt(&nonce, keypair.to_bytes().as_slice())
+            .unwrap();
+        let envelope = serde_json::json!({
+            "encrypted": true,
+            "salt": encode_hex(&salt),
+            "nonce": encode_hex(&nonce),
+            "ciphertext": encode_hex(&ciphertext),
+        });
+        let decrypted = decrypt_keypair(&envelope.to_string(), "secret").unwrap();
+        assert_eq!(decrypted.pubkey(), keypair.pubkey());
+
+        let mut envelope = envelope_with(&keypair, "secret");
+        assert_eq!(envelope["version"], ENCRYPTED_KEYPAIR_VERSION);
+        envelope["version"] = 3.into();
+        let err = decrypt_keypair(&envelope.to_string(), "secret").unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "Unsupported encrypted keypair version 3, this keygen reads versions 1 to 2"
+        );
+    }
+
+    #[test]
+    fn test_encrypted_keypair_rejects_excessive_scrypt_cost() {
+        let mut envelope = envelope_with(&Keypair::new(), "secret");
+        envelope["scrypt"]["log_n"] = (MAX_SCRYPT_LOG_N + 1).into();
+        let err = decrypt_keypair(&envelope.to_string(), "secret").unwrap_err();
+        assert!(err.to_string().contains("is above the maximum of 20"));
+    }
+
+    #[test]
+    fn test_keypair_from_secret_hex() {
+        let keypair = Keypair::new();
+        let keypair_hex = encode_hex(&keypair.to_bytes());
+        for hex in [
+            keypair_hex.clone(),
+            keypair_hex[..64].to_string(),
+            format!("0x{keypair_hex}\n"),
+        ] {
+            assert_eq!(
+                keypair_from_secret_hex(&hex).unwrap().to_bytes(),
+                keypair.to_bytes()
+            );
+        }
+        assert_eq!(
+            keypair_from_secret_hex(&keypair_hex[..62])
+                .unwrap_err()
+                .to_string(),
+            "Expected 64 or 32 hex encoded bytes, found 31"
+        );
+        let mismatched_hex = format!("{}{}", &keypair_hex[..64], "00".repeat(32));
+        assert!(
+            keypair_from_secret_hex(&mismatched_hex)
+                .unwrap_err()
+                .to_string()
+                .starts_with("Invalid keypair: ")
+        );
+    }
+
+    #[test]
+    fn test_decode_hex() {
+        assert_eq!(decode_hex("00ff7F"), Ok(vec![0, 255, 127]));
+        assert_eq!(decode_hex("0x0a"), Ok(vec![10]));
+        assert_eq!(decode_hex(""), Ok(vec![]));
+        assert_eq!(
+            decode_hex("abc"),
+            Err("Hex string must consist of an even number of hex digits".to_string())
+        );
+        for pair in ["0g", "+1", "-1"] {
+            assert_eq!(
+                decode_hex(pair),
+                Err(format!("Invalid hex digits '{pair}'"))
+            );
+        }
+    }
+
+    #[test]
+    fn test_zeroizing_wipes_secret_bytes() {
+        use zeroize::Zeroize;
+
+        // Dropping a `Zeroizing` wrapper runs this same zeroize on the buffer it holds.
+        let mut bytes = Zeroizing::new(Keypair::new().to_bytes());
+        assert_ne!(*bytes, [0; KEYPAIR_LENGTH]);
+        bytes.zeroize();
+        assert_eq!(*bytes, [0; KEYPAIR_LENGTH]);
+    }
+
+    #[test]
+    fn test_verify_keypair() {
+        let keypair = Keypair::new();
+        assert!(verify_keypair(&keypair, &keypair.pubkey()));
+        assert!(!verify_keypair(&keypair, &Keypair::new().pubkey()));
+    }
+
+    #[test]
+    fn test_backup_keypair_file() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("id.json");
+        let path = path.to_str().unwrap();
+        fs::write(path, "keypair").unwrap();
+
+        let backup_path = backup_keypair_file(path).unwrap();
+        assert!(
+            backup_path.starts_with(&format!("{path}.bak-")),
+            "{backup_path}"
+        );
+        assert!(fs::symlink_metadata(path).is_err());
+        assert_eq!(fs::read_to_string(&backup_path).unwrap(), "keypair");
+
+        let missing = dir.path().join("missing.json");
+        let missing = missing.to_str().unwrap();
+        assert!(
+            backup_keypair_file(missing)
+                .unwrap_err()
+                .to_string()
+                .starts_with(&format!("Unable to back up {missing} to {missing}.bak-"))
+        );
+    }
+
+    #[test]
+    fn test_keypair_from_raw_seed() {
+        for seed in [[7; 32].as_slice(), [7; 64].as_slice()] {
+            let keypair = keypair_from_seed(seed).unwrap();
+            for encoded in [
+                encode_hex(seed),
+                bs58::encode(seed).into_string(),
+                format!(" {}\n", encode_hex(seed)),
+            ] {
+                assert_eq!(
+                    keypair_from_raw_seed(&encoded).unwrap().to_bytes(),
+                    keypair.to_bytes()
+                );
+            }
+        }
+        assert_eq!(
+            keypair_from_raw_seed(&bs58::encode([7; 16]).into_string())
+                .unwrap_err()
+                .to_string(),
+            "Expected a seed of 32 or 64 bytes, found 16"
+        );
+        assert_eq!(
+            keypair_from_raw_seed("0O").unwrap_err().to_string(),
+            "Seed is neither valid hex nor valid base58"
+        );
+    }
+
+    #[test]
+    fn test_keypair_format_name() {
+        assert_eq!(keypair_format_name("[1,2,3]"), "JSON array");
+        assert_eq!(
+            keypair_format_name("\n {\"encrypted\": true}"),
+            ENCRYPTED_KEYPAIR_FORMAT
+        );
+        assert_eq!(
+            keypair_format_name(&Keypair::new().to_base58_string()),
+            "base58"
+        );
+    }
+
+    #[test]
+    fn test_encode_pubkey() {
+        let pubkey = Pubkey::new_from_array([0xab; 32]);
+        assert_eq!(encode_pubkey(&pubkey, "hex"), "ab".repeat(32));
+
+        for pubkey in [pubkey, Pubkey::new_unique()] {
+            assert_eq!(
+                encode_pubkey(&pubkey, "base58").parse::<Pubkey>(),
+                Ok(pubkey)
+            );
+            let hex = decode_hex(&encode_pubkey(&pubkey, "hex")).unwrap();
+            assert_eq!(Pubkey::try_from(hex.as_slice()).unwrap(), pubkey);
+            let base64 = BASE64_STANDARD
+                .decode(encode_pubkey(&pubkey, "base64"))
+                .unwrap();
+            assert_eq!(Pubkey::try_from(base64.as_slice()).unwrap(), pubkey);
+        }
+    }
+}
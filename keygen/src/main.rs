@@ -1,22 +1,65 @@
 mod keypair;
 mod mnemonic;
+mod signer_uri;
 
+use crate::keypair::{
+    ENCRYPTED_KEYPAIR_FORMAT, FIXTURE_SECRET_KEYS, backup_keypair_file, confirm_and_clear_screen,
+    confirm_pubkey, confirm_seed_phrase, encode_pubkey, encrypt_keypair, fixture_keypair,
+    keypair_format_name, keypair_from_raw_seed, keypair_from_secret_hex,
+    prompt_encryption_password, prompt_passphrase, prompt_seed_phrase, read_keypair_any_format,
+    read_secret, sign_domain_message, verify_domain_message, verify_keypair,
+};
 use crate::mnemonic::{
-    acquire_passphrase_and_message, language_arg, no_passphrase_arg, try_get_language,
-    try_get_word_count, word_count_arg,
+    ENTROPY_FILE_ARG, ENTROPY_HEX_ARG, LANGUAGE_ARG, RECOVERY_CHECKLIST,
+    acquire_passphrase_and_message, derivation_path_arg, entropy_file_arg, entropy_hex_arg,
+    keypair_from_bip39_seed, keypair_from_seed_phrase, language_arg, mnemonic_from_entropy_file,
+    mnemonic_from_entropy_hex, no_passphrase_arg, parse_derivation_path, print_languages,
+    prompt_mnemonic, recovery_language_arg, seed_from_seed_phrase, try_get_derivation_path,
+    try_get_language, try_get_recovery_language, try_get_word_count, validate_seed_phrase,
+    word_count_arg,
 };
+use crate::signer_uri::{is_signer_uri, parse_signer_uri};
 use bip39::{Mnemonic, MnemonicType, Seed};
+use chrono::{DateTime, SecondsFormat, Utc};
+use clap::builder::{OsStr, Resettable};
+use clap::parser::ValueSource;
 use clap::{Arg, ArgAction, ArgMatches, Command, crate_description, crate_name, crate_version};
+use clap_complete::{Shell, generate};
+use qrcode::QrCode;
+use qrcode::render::unicode::Dense1x2;
 use solana_cli_config::Config;
-use solana_keypair::{Keypair, keypair_from_seed, write_keypair, write_keypair_file};
+use solana_derivation_path::DerivationPath;
+use solana_keypair::{Keypair, keypair_from_seed};
+use solana_pubkey::Pubkey;
+use solana_signature::Signature;
 use solana_signer::Signer;
-use std::error;
-use std::path::Path;
+use std::fs::{self, File, OpenOptions};
+use std::io::{self, IsTerminal, Write, stderr, stdin, stdout};
+use std::path::{Path, PathBuf};
+use std::process;
+use std::time::{SystemTime, UNIX_EPOCH};
+use std::{env, error};
+use terminal_size::{Width, terminal_size_of};
+use zeroize::Zeroizing;
 
 const CONFIG_FILE: &str = "config_file";
 
-fn main() -> Result<(), Box<dyn error::Error>> {
-    let matches = Command::new(crate_name!())
+// BIP44 account indexes are hardened, so they must stay below 2^31
+const MAX_BIP44_ACCOUNT: i64 = 1 << 31;
+
+/// Returns the `m/44'/501'/<account>'/0'` derivation path used by recover --list and
+/// --at-index.
+fn bip44_account_path(account: u32) -> DerivationPath {
+    DerivationPath::new_bip44(Some(account), Some(0))
+}
+
+fn app(default_outfile: Option<String>) -> Command {
+    // Without a default keypair path the arguments that fall back on it have no default value,
+    // and leaving them out is reported by `try_get_keypair_path`.
+    let default_outfile = default_outfile.map_or(Resettable::Reset, |path| {
+        Resettable::Value(OsStr::from(path))
+    });
+    Command::new(crate_name!())
         .about(crate_description!())
         .version(crate_version!())
         .subcommand_required(true)
@@ -36,70 +79,712 @@ fn main() -> Result<(), Box<dyn error::Error>> {
                         .short('o')
                         .long("outfile")
                         .value_name("FILEPATH")
-                        .help("Path to generated file"),
+                        .default_value(default_outfile.clone())
+                        .help(
+                            "Path to generated file, or - to write the keypair to stdout and \
+                             everything else to stderr",
+                        ),
                 )
                 .arg(
                     Arg::new("force")
                         .short('f')
                         .long("force")
                         .action(ArgAction::SetTrue)
-                        .help("Overwrite the output file if it exists"),
+                        .help(
+                            "Overwrite the output file if it exists, moving the old file to \
+                             <FILEPATH>.bak-<TIMESTAMP> unless --no-backup",
+                        ),
+                )
+                .arg(
+                    Arg::new(NO_OUTFILE_ARG.name)
+                        .long(NO_OUTFILE_ARG.long)
+                        .action(ArgAction::SetTrue)
+                        .conflicts_with("outfile")
+                        .help(NO_OUTFILE_ARG.help),
                 )
                 .arg(
                     Arg::new("silent")
                         .short('s')
                         .long("silent")
                         .action(ArgAction::SetTrue)
-                        .help("Do not display seed phrase."),
+                        .help(
+                            "Do not display seed phrase. Also disables prompts, so \
+                             --no-bip39-passphrase is required unless importing a secret",
+                        ),
+                )
+                .arg(
+                    Arg::new("from_secret_hex")
+                        .long("from-secret-hex")
+                        .value_name("HEX")
+                        .help(
+                            "Import the keypair from its 64 keypair bytes or 32 secret key \
+                             bytes in hex instead of generating a seed phrase",
+                        ),
+                )
+                .arg(
+                    Arg::new("fixture")
+                        .long("fixture")
+                        .value_name("NAME")
+                        .value_parser(
+                            FIXTURE_SECRET_KEYS
+                                .iter()
+                                .map(|(name, _)| *name)
+                                .collect::<Vec<_>>(),
+                        )
+                        .conflicts_with("from_secret_hex")
+                        .help(
+                            "Write the deterministic test keypair of this name, so tests \
+                             share identities. Anyone can derive it, never fund it",
+                        ),
+                )
+                .arg(
+                    Arg::new("clear_after")
+                        .long("clear-after")
+                        .action(ArgAction::SetTrue)
+                        .help(
+                            "Clear the terminal and its scrollback once the seed phrase \
+                             has been recorded. Ignored with --silent or when stdout is not \
+                             a terminal",
+                        ),
+                )
+                .arg(
+                    Arg::new("confirm_seed_phrase")
+                        .long("confirm-seed-phrase")
+                        .action(ArgAction::SetTrue)
+                        .conflicts_with_all(["silent", "from_secret_hex", "fixture"])
+                        .help(
+                            "Once the seed phrase has been recorded, clear the screen and ask \
+                             for three of its words, not finishing until they match. Needs a \
+                             terminal",
+                        ),
+                )
+                .arg(
+                    Arg::new("recovery_checklist")
+                        .long("recovery-checklist")
+                        .action(ArgAction::SetTrue)
+                        .help(
+                            "Print the steps to safely record and check the seed phrase. \
+                             Ignored with --silent",
+                        ),
+                )
+                .arg(
+                    Arg::new("output")
+                        .long("output")
+                        .value_name("FORMAT")
+                        .value_parser(["display", "json"])
+                        .default_value("display")
+                        .conflicts_with_all([
+                            "clear_after",
+                            "confirm_seed_phrase",
+                            "recovery_checklist",
+                        ])
+                        .help(
+                            "Format of the result. json prints the pubkey, the seed phrase \
+                             unless --silent, and the output path as one JSON object on stdout",
+                        ),
+                )
+                .arg(pubkey_encoding_arg().help("Encoding of the pubkey in --output json"))
+                .arg(qr_arg().help(
+                    "Also show the base58 pubkey as a QR code, to scan it into a phone wallet. \
+                     Shown on stderr with --outfile - or --output json",
+                ))
+                .arg(
+                    Arg::new("attest")
+                        .long("attest")
+                        .value_name("MESSAGE")
+                        .help(
+                            "Sign MESSAGE with the new keypair, as sign-message does, and print \
+                             the pubkey, message and base58 signature, proving control of the \
+                             key at creation. Printed even with --silent",
+                        ),
                 )
+                .arg(entropy_file_arg().conflicts_with_all(["from_secret_hex", "fixture"]))
+                .arg(entropy_hex_arg().conflicts_with_all([
+                    ENTROPY_FILE_ARG.name,
+                    "from_secret_hex",
+                    "fixture",
+                ]))
+                .arg(no_backup_arg().conflicts_with(NO_OUTFILE_ARG.name))
+                .arg(create_dirs_arg().conflicts_with(NO_OUTFILE_ARG.name))
+                .arg(encrypt_arg().conflicts_with(NO_OUTFILE_ARG.name))
                 .key_generation_common_args(),
         )
-        .try_get_matches()
-        .unwrap_or_else(|e| e.exit());
+        .subcommand(
+            Command::new("recover")
+                .about("Recover keypair from seed phrase and optional BIP39 passphrase")
+                .arg(
+                    Arg::new("outfile")
+                        .short('o')
+                        .long("outfile")
+                        .value_name("FILEPATH")
+                        .default_value(default_outfile.clone())
+                        .help("Path to generated file"),
+                )
+                .arg(
+                    Arg::new("force")
+                        .short('f')
+                        .long("force")
+                        .action(ArgAction::SetTrue)
+                        .help(
+                            "Overwrite the output file if it exists, moving the old file to \
+                             <FILEPATH>.bak-<TIMESTAMP> unless --no-backup",
+                        ),
+                )
+                .arg(
+                    Arg::new("no_confirm")
+                        .long("no-confirm")
+                        .action(ArgAction::SetTrue)
+                        .help(
+                            "Do not ask to confirm the recovered pubkey before writing the \
+                             keypair file. Implied when stdin is not a terminal",
+                        ),
+                )
+                .arg(recovery_language_arg())
+                .arg(no_passphrase_arg())
+                .arg(
+                    Arg::new("seed")
+                        .long("seed")
+                        .value_name("HEX_OR_BASE58")
+                        .conflicts_with_all(["seed_file", "seed_stdin", LANGUAGE_ARG.name])
+                        .help(
+                            "Recover from a raw 32 or 64 byte seed instead of a seed phrase. \
+                             It may end up in your shell history, prefer --seed-file or \
+                             --seed-stdin",
+                        ),
+                )
+                .arg(
+                    Arg::new("seed_file")
+                        .long("seed-file")
+                        .value_name("FILEPATH")
+                        .conflicts_with_all(["seed_stdin", LANGUAGE_ARG.name])
+                        .help("Recover from a raw seed in hex or base58 read from this file"),
+                )
+                .arg(
+                    Arg::new("seed_stdin")
+                        .long("seed-stdin")
+                        .action(ArgAction::SetTrue)
+                        .conflicts_with(LANGUAGE_ARG.name)
+                        .help("Recover from a raw seed in hex or base58 read from stdin"),
+                )
+                .arg(
+                    Arg::new("list")
+                        .long("list")
+                        .value_name("N")
+                        .value_parser(clap::value_parser!(u32).range(1..=MAX_BIP44_ACCOUNT))
+                        .conflicts_with_all([
+                            "outfile",
+                            "force",
+                            "no_confirm",
+                            "seed",
+                            "seed_file",
+                            "seed_stdin",
+                            NO_BACKUP_ARG.name,
+                            ENCRYPT_ARG.name,
+                        ])
+                        .help(
+                            "Print the pubkeys of the first N accounts of the seed phrase, \
+                             derived along m/44'/501'/<INDEX>'/0', without writing any file",
+                        ),
+                )
+                .arg(
+                    Arg::new("at_index")
+                        .long("at-index")
+                        .value_name("INDEX")
+                        .value_parser(clap::value_parser!(u32).range(..MAX_BIP44_ACCOUNT))
+                        .conflicts_with_all(["list", "seed", "seed_file", "seed_stdin"])
+                        .help(
+                            "Recover the account at this index of the seed phrase, derived \
+                             along m/44'/501'/<INDEX>'/0', as listed by --list",
+                        ),
+                )
+                .arg(no_backup_arg())
+                .arg(create_dirs_arg())
+                .arg(encrypt_arg()),
+        )
+        .subcommand(
+            Command::new("change-passphrase")
+                .about(
+                    "Check the BIP39 passphrase of the seed phrase behind a keypair file and \
+                     pick a new one. The keypair file itself is never changed",
+                )
+                .arg(
+                    Arg::new("keypair")
+                        .long("keypair")
+                        .value_name("FILEPATH")
+                        .default_value(default_outfile.clone())
+                        .help(
+                            "Keypair derived from the seed phrase with the current passphrase, \
+                             or env:VAR_NAME to read it from an environment variable",
+                        ),
+                )
+                .arg(recovery_language_arg())
+                .arg(derivation_path_arg()),
+        )
+        .subcommand(
+            Command::new("rotate")
+                .about(
+                    "Derive a new keypair from the seed phrase behind an existing one, along \
+                     another derivation path, and record the old and new pubkeys in a lineage \
+                     manifest",
+                )
+                .arg(
+                    Arg::new("from")
+                        .long("from")
+                        .value_name("FILEPATH")
+                        .required(true)
+                        .help(
+                            "Keypair being rotated out, which the seed phrase must derive, or \
+                             env:VAR_NAME to read it from an environment variable",
+                        ),
+                )
+                .arg(
+                    Arg::new("from_derivation_path")
+                        .long("from-derivation-path")
+                        .value_name("DERIVATION_PATH")
+                        .help(
+                            "Derivation path of the --from keypair, either as ACCOUNT/CHANGE or \
+                             as a full path. Without it the keypair is expected to come from \
+                             the seed phrase directly",
+                        ),
+                )
+                .arg(derivation_path_arg().required(true))
+                .arg(
+                    Arg::new("outfile")
+                        .short('o')
+                        .long("outfile")
+                        .value_name("FILEPATH")
+                        .required(true)
+                        .help("Path to the rotated keypair file"),
+                )
+                .arg(
+                    Arg::new("manifest")
+                        .long("manifest")
+                        .value_name("FILEPATH")
+                        .help("Path to the lineage manifest [default: <OUTFILE>.lineage.json]"),
+                )
+                .arg(
+                    Arg::new("force")
+                        .short('f')
+                        .long("force")
+                        .action(ArgAction::SetTrue)
+                        .help(
+                            "Overwrite the output files if they exist, moving an old keypair \
+                             file to <FILEPATH>.bak-<TIMESTAMP> unless --no-backup",
+                        ),
+                )
+                .arg(recovery_language_arg())
+                .arg(no_passphrase_arg())
+                .arg(no_backup_arg())
+                .arg(create_dirs_arg()),
+        )
+        .subcommand(
+            Command::new("pubkey")
+                .about("Display the pubkey from a keypair file or a seed phrase")
+                .arg(
+                    Arg::new("keypair")
+                        .index(1)
+                        .value_name("KEYPAIR")
+                        .default_value(default_outfile.clone())
+                        .help(
+                            "Filepath of the keypair, or env:VAR_NAME to read it from an \
+                             environment variable",
+                        ),
+                )
+                .arg(
+                    Arg::new("from_seed_phrase")
+                        .long("from-seed-phrase")
+                        .action(ArgAction::SetTrue)
+                        .conflicts_with("keypair")
+                        .help(
+                            "Derive the pubkey from a seed phrase, prompted for or read from \
+                             stdin, without writing anything to disk",
+                        ),
+                )
+                .arg(recovery_language_arg().requires("from_seed_phrase"))
+                .arg(no_passphrase_arg().requires("from_seed_phrase"))
+                .arg(derivation_path_arg().requires("from_seed_phrase"))
+                .arg(
+                    Arg::new("copy")
+                        .long("copy")
+                        .action(ArgAction::SetTrue)
+                        .help(
+                            "Also copy the pubkey to the system clipboard. Requires a build \
+                             with the clipboard feature",
+                        ),
+                )
+                .arg(pubkey_encoding_arg())
+                .arg(qr_arg()),
+        )
+        .subcommand(
+            Command::new("verify")
+                .about(
+                    "Verify a keypair can sign for a pubkey. Exits with 2 when it cannot and \
+                     with 3 when the keypair is unreadable",
+                )
+                .arg(
+                    Arg::new("pubkey")
+                        .index(1)
+                        .value_name("PUBKEY")
+                        .value_parser(clap::value_parser!(Pubkey))
+                        .required(true)
+                        .help("Public key"),
+                )
+                .arg(
+                    Arg::new("keypair")
+                        .index(2)
+                        .value_name("KEYPAIR")
+                        .default_value(default_outfile.clone())
+                        .help(
+                            "Filepath of the keypair, or env:VAR_NAME to read it from an \
+                             environment variable",
+                        ),
+                )
+                .arg(
+                    Arg::new("output")
+                        .long("output")
+                        .value_name("FORMAT")
+                        .value_parser(["display", "json"])
+                        .default_value("display")
+                        .help("Format of the verification result"),
+                ),
+        )
+        .subcommand(
+            Command::new("sign-message")
+                .about(
+                    "Sign an off-chain message with a keypair. The message is signed behind a \
+                     fixed prefix, so the signature cannot be replayed as a transaction",
+                )
+                .arg(
+                    Arg::new("message")
+                        .index(1)
+                        .value_name("MESSAGE")
+                        .required(true)
+                        .help("Message to sign, or with --message-file the file holding it"),
+                )
+                .arg(
+                    Arg::new("keypair")
+                        .index(2)
+                        .value_name("KEYPAIR")
+                        .default_value(default_outfile.clone())
+                        .help(
+                            "Filepath of the keypair, or env:VAR_NAME to read it from an \
+                             environment variable",
+                        ),
+                )
+                .arg(message_file_arg())
+                .arg(
+                    Arg::new("output")
+                        .long("output")
+                        .value_name("FORMAT")
+                        .value_parser(["display", "json"])
+                        .default_value("display")
+                        .help("Format of the base58 signature"),
+                ),
+        )
+        .subcommand(
+            Command::new("verify-message")
+                .about("Verify a signature made by sign-message. Exits with 1 when it is not valid")
+                .arg(
+                    Arg::new("pubkey")
+                        .index(1)
+                        .value_name("PUBKEY")
+                        .value_parser(clap::value_parser!(Pubkey))
+                        .required(true)
+                        .help("Public key of the signer"),
+                )
+                .arg(
+                    Arg::new("message")
+                        .index(2)
+                        .value_name("MESSAGE")
+                        .required(true)
+                        .help("Signed message, or with --message-file the file holding it"),
+                )
+                .arg(
+                    Arg::new("signature")
+                        .index(3)
+                        .value_name("SIGNATURE")
+                        .value_parser(clap::value_parser!(Signature))
+                        .required(true)
+                        .help("Base58 signature"),
+                )
+                .arg(message_file_arg())
+                .arg(
+                    Arg::new("output")
+                        .long("output")
+                        .value_name("FORMAT")
+                        .value_parser(["display", "json"])
+                        .default_value("display")
+                        .help("Format of the verification result"),
+                ),
+        )
+        .subcommand(
+            Command::new("convert")
+                .about("Convert a keypair between the JSON byte array and base58 formats")
+                .arg(
+                    Arg::new("from")
+                        .long("from")
+                        .value_name("FILEPATH")
+                        .required(true)
+                        .help(
+                            "Keypair to convert, in either format. Use \"-\" to read it from \
+                             stdin, or env:VAR_NAME to read it from an environment variable",
+                        ),
+                )
+                .arg(
+                    Arg::new("to")
+                        .long("to")
+                        .value_name("FILEPATH")
+                        .required(true)
+                        .help("Path to converted file. Use \"-\" to write it to stdout"),
+                )
+                .arg(
+                    Arg::new("output_format")
+                        .long("output-format")
+                        .value_name("FORMAT")
+                        .value_parser(["json-array", "base58"])
+                        .default_value("json-array")
+                        .help("Format of the converted keypair"),
+                )
+                .arg(encrypt_arg().conflicts_with("output_format"))
+                .arg(no_backup_arg())
+                .arg(create_dirs_arg())
+                .arg(
+                    Arg::new("force")
+                        .short('f')
+                        .long("force")
+                        .action(ArgAction::SetTrue)
+                        .help(
+                            "Overwrite the output file if it exists, moving the old file to \
+                             <FILEPATH>.bak-<TIMESTAMP> unless --no-backup",
+                        ),
+                ),
+        )
+        .subcommand(
+            Command::new("change-encryption")
+                .about("Encrypt a keypair file, or change the password of an encrypted one")
+                .arg(
+                    Arg::new("keypair")
+                        .index(1)
+                        .value_name("KEYPAIR")
+                        .default_value(default_outfile.clone())
+                        .help("Filepath of the keypair"),
+                ),
+        )
+        .subcommand(
+            Command::new("decrypt")
+                .about("Write an encrypted keypair file back out in the plain JSON array format")
+                .arg(
+                    Arg::new("keypair")
+                        .index(1)
+                        .value_name("KEYPAIR")
+                        .default_value(default_outfile.clone())
+                        .help("Filepath of the encrypted keypair"),
+                )
+                .arg(
+                    Arg::new("outfile")
+                        .short('o')
+                        .long("outfile")
+                        .value_name("FILEPATH")
+                        .required(true)
+                        .help("Path to decrypted file. Use \"-\" to write it to stdout"),
+                )
+                .arg(no_backup_arg())
+                .arg(create_dirs_arg())
+                .arg(
+                    Arg::new("force")
+                        .short('f')
+                        .long("force")
+                        .action(ArgAction::SetTrue)
+                        .help(
+                            "Overwrite the output file if it exists, moving the old file to \
+                             <FILEPATH>.bak-<TIMESTAMP> unless --no-backup",
+                        ),
+                ),
+        )
+        .subcommand(
+            Command::new("inspect")
+                .about(
+                    "Show the pubkey, format, timestamps and permissions of a keypair file \
+                     without printing any private key material",
+                )
+                .arg(
+                    Arg::new("keypair")
+                        .short('k')
+                        .long("keypair")
+                        .value_name("FILEPATH")
+                        .default_value(default_outfile.clone())
+                        .help("Filepath of the keypair"),
+                ),
+        )
+        .subcommand(
+            Command::new("validate-phrase")
+                .about("Check that a seed phrase is a valid BIP39 mnemonic without recovering it")
+                .arg(language_arg())
+                .arg(Arg::new("phrase").long("phrase").value_name("PHRASE").help(
+                    "Seed phrase to check instead of prompting for it. Note that it \
+                             may end up in the shell history",
+                )),
+        )
+        .subcommand(
+            Command::new("list-languages")
+                .about("List the supported seed phrase languages with a sample word of each"),
+        )
+        .subcommand(
+            Command::new("completions")
+                .about("Print the completion script for a shell to stdout")
+                .hide(true)
+                .arg(
+                    Arg::new("shell")
+                        .long("shell")
+                        .value_name("SHELL")
+                        .value_parser(clap::value_parser!(Shell))
+                        .required(true)
+                        .help("Shell to generate the completion script for"),
+                ),
+        )
+}
 
-    let _ = if let Some(config_file) = matches.try_get_one::<String>(CONFIG_FILE)? {
-        Config::load(config_file)?
-    } else {
-        Config::default()
+fn main() -> Result<(), Box<dyn error::Error>> {
+    // The default --outfile comes from the config file, so find out which one is used first.
+    let config_file = app(None)
+        .ignore_errors(true)
+        .try_get_matches()
+        .ok()
+        .and_then(|matches| matches.get_one::<String>(CONFIG_FILE).cloned());
+    let default_outfile = match load_config(config_file.as_deref())? {
+        Some(config) => Ok(config.keypair_path),
+        None => default_keypair_path().map_err(|err| err.to_string()),
     };
 
+    let matches = app(default_outfile.clone().ok())
+        .try_get_matches()
+        .unwrap_or_else(|e| e.exit());
+
     if let Some(subcommand) = matches.subcommand() {
         match subcommand {
             ("new", matches) => {
-                let mut path = std::env::home_dir().expect("home directory");
-                let outfile = if matches.try_contains_id("outfile")? {
-                    matches.get_one::<String>("outfile").map(|s| s.as_str())
-                } else if matches.try_contains_id(NO_OUTFILE_ARG.name)? {
+                let outfile = if matches.get_flag(NO_OUTFILE_ARG.name) {
                     None
                 } else {
-                    path.extend([".config", "blockchain", "id.json"]);
-                    Some(path.to_str().unwrap())
+                    Some(try_get_keypair_path(
+                        matches,
+                        "outfile",
+                        "--outfile, or --no-outfile to only print the seed phrase and pubkey",
+                        &default_outfile,
+                    )?)
                 };
                 let word_count = try_get_word_count(matches)?.unwrap();
                 let language = try_get_language(matches)?.unwrap();
 
                 let silent = matches.get_flag("silent");
-                if !silent {
-                    println!("Generating a new keypair");
+                let secret_hex = matches.get_one::<String>("from_secret_hex");
+                let fixture = matches.get_one::<String>("fixture");
+                let json = matches.get_one::<String>("output").unwrap() == "json";
+                // With `--outfile -` stdout carries only the keypair, so that it can be piped,
+                // and with `--output json` only the JSON. Everything else goes to stderr.
+                let to_stdout = outfile == Some(STDOUT_OUTFILE_TOKEN);
+                if to_stdout && json {
+                    return Err(
+                        "--output json cannot be used with --outfile -, both write to stdout"
+                            .into(),
+                    );
+                }
+                let (mut info, info_is_terminal): (Box<dyn Write>, bool) = if to_stdout || json {
+                    (Box::new(stderr()), stderr().is_terminal())
+                } else {
+                    (Box::new(stdout()), stdout().is_terminal())
+                };
+                let confirm = matches.get_flag("confirm_seed_phrase");
+                if confirm && (!stdin().is_terminal() || !info_is_terminal) {
+                    return Err(
+                        "--confirm-seed-phrase needs a terminal to ask for the words on, \
+                         but stdin or the output is not one"
+                            .into(),
+                    );
+                }
+                if !silent && secret_hex.is_none() && fixture.is_none() {
+                    writeln!(info, "Generating a new keypair")?;
                 }
 
-                let mnemonic_type = MnemonicType::for_word_count(word_count)?;
-                let mnemonic = Mnemonic::new(mnemonic_type, language);
-                let (passphrase, passphrase_message) = acquire_passphrase_and_message(matches)
-                    .map_err(|err| format!("Unable to acquire passphrase: {err}"))?;
-                let seed = Seed::new(&mnemonic, &passphrase);
-                let keypair = keypair_from_seed(seed.as_bytes())?;
+                // An imported secret or a fixture has no seed phrase to show.
+                let (keypair, mnemonic_and_message) = if let Some(secret_hex) = secret_hex {
+                    (keypair_from_secret_hex(secret_hex)?, None)
+                } else if let Some(fixture) = fixture {
+                    (fixture_keypair(fixture).unwrap(), None)
+                } else {
+                    let mnemonic_type = MnemonicType::for_word_count(word_count)?;
+                    let mnemonic =
+                        if let Some(path) = matches.get_one::<String>(ENTROPY_FILE_ARG.name) {
+                            eprintln!(
+                                "Warning: the seed phrase is derived from {path} alone, anyone \
+                                 with the file can recreate the keypair"
+                            );
+                            mnemonic_from_entropy_file(path, mnemonic_type, language)?
+                        } else if let Some(hex) = matches.get_one::<String>(ENTROPY_HEX_ARG.name) {
+                            eprintln!(
+                                "Warning: the seed phrase is derived from the given entropy alone, \
+                             only use it for test vectors"
+                            );
+                            mnemonic_from_entropy_hex(hex, mnemonic_type, language)?
+                        } else {
+                            Mnemonic::new(mnemonic_type, language)
+                        };
+                    let interactive = !silent && stdin().is_terminal();
+                    let (passphrase, passphrase_message) =
+                        acquire_passphrase_and_message(matches, interactive)
+                            .map_err(|err| format!("Unable to acquire passphrase: {err}"))?;
+                    let seed = Seed::new(&mnemonic, &passphrase);
+                    let keypair = keypair_from_seed(seed.as_bytes())?;
+                    (keypair, Some((mnemonic, passphrase_message)))
+                };
 
                 if let Some(outfile) = outfile {
-                    check_for_overwrite(outfile, matches)?;
-                    output_keypair(&keypair, outfile, "new")
-                        .map_err(|err| format!("Unable to write {outfile}: {err}"))?;
+                    let backup = if to_stdout {
+                        false
+                    } else {
+                        check_outfile_path(outfile, "outfile", matches)?;
+                        check_for_overwrite(outfile, matches)?;
+                        try_get_backup(matches)
+                    };
+                    output_keypair(
+                        &keypair,
+                        outfile,
+                        "new",
+                        matches.get_flag("force"),
+                        backup,
+                        try_get_encoding(matches, KeypairEncoding::JsonArray)?,
+                        &mut info,
+                    )
+                    .map_err(|err| format!("Unable to write {outfile}: {err}"))?;
                 }
 
-                if !silent {
+                let attestation = matches
+                    .get_one::<String>("attest")
+                    .map(|message| (message, sign_domain_message(&keypair, message.as_bytes())));
+                if json {
+                    let mut output = serde_json::json!({
+                        "pubkey": encode_pubkey(
+                            &keypair.pubkey(),
+                            matches.get_one::<String>(PUBKEY_ENCODING_ARG.name).unwrap(),
+                        ),
+                        "path": outfile,
+                    });
+                    if !silent && let Some((mnemonic, _)) = &mnemonic_and_message {
+                        output["seedPhrase"] = mnemonic.phrase().into();
+                    }
+                    if let Some((message, signature)) = &attestation {
+                        output["attestation"] = serde_json::json!({
+                            "message": message,
+                            "signature": signature.to_string(),
+                        });
+                    }
+                    println!("{output}");
+                } else if !silent && let Some((mnemonic, passphrase_message)) = mnemonic_and_message
+                {
                     let phrase: &str = mnemonic.phrase();
                     let divider = String::from_utf8(vec![b'='; phrase.len()]).unwrap();
-                    println!(
+                    writeln!(
+                        info,
                         "{}\npubkey: {}\n{}\nSave this seed phrase{} to recover your new keypair:\n{}\n{}",
                         &divider,
                         keypair.pubkey(),
@@ -107,8 +792,420 @@ fn main() -> Result<(), Box<dyn error::Error>> {
                         passphrase_message,
                         phrase,
                         &divider
+                    )?;
+                    if matches.get_flag("clear_after") || confirm {
+                        confirm_and_clear_screen(&mut stdin().lock(), &mut info, info_is_terminal)?;
+                    }
+                    if confirm {
+                        confirm_seed_phrase(&mut stdin().lock(), &mut info, phrase)?;
+                    }
+                    if matches.get_flag("recovery_checklist") {
+                        writeln!(info, "{RECOVERY_CHECKLIST}")?;
+                    }
+                } else if !silent {
+                    writeln!(info, "pubkey: {}", keypair.pubkey())?;
+                }
+                if !json && let Some((message, signature)) = attestation {
+                    writeln!(
+                        info,
+                        "Attestation:\n  pubkey: {}\n  message: {message}\n  signature: {signature}",
+                        keypair.pubkey()
+                    )?;
+                }
+                if matches.get_flag(QR_ARG.name) {
+                    let columns = if to_stdout || json {
+                        terminal_size_of(stderr())
+                    } else {
+                        terminal_size_of(stdout())
+                    }
+                    .map(|(Width(columns), _)| columns);
+                    writeln!(info, "{}", pubkey_qr_code(&keypair.pubkey(), columns)?)?;
+                }
+            }
+            ("recover", matches) if matches.contains_id("list") => {
+                let count = *matches.get_one::<u32>("list").unwrap();
+                let language = try_get_recovery_language(matches)?;
+                let seed = seed_from_seed_phrase(matches, language)?;
+                println!("{:<7} {:<20} Pubkey", "Index", "Derivation path");
+                for index in 0..count {
+                    let derivation_path = bip44_account_path(index);
+                    let pubkey =
+                        keypair_from_bip39_seed(&seed, Some(derivation_path.clone()))?.pubkey();
+                    println!("{index:<7} {:<20} {pubkey}", format!("{derivation_path:?}"));
+                }
+            }
+            ("recover", matches) => {
+                let outfile =
+                    try_get_keypair_path(matches, "outfile", "--outfile", &default_outfile)?;
+                if outfile != STDOUT_OUTFILE_TOKEN {
+                    check_outfile_path(outfile, "outfile", matches)?;
+                    check_for_overwrite(outfile, matches)?;
+                }
+                let backup = try_get_backup(matches);
+                let keypair = if let Some(seed) = matches.get_one::<String>("seed") {
+                    eprintln!(
+                        "Warning: a seed given on the command line may be kept in your shell \
+                         history, prefer --seed-file or --seed-stdin"
+                    );
+                    keypair_from_raw_seed(seed)?
+                } else if let Some(path) = matches.get_one::<String>("seed_file") {
+                    let seed = Zeroizing::new(
+                        fs::read_to_string(path)
+                            .map_err(|err| format!("Unable to read {path}: {err}"))?,
+                    );
+                    keypair_from_raw_seed(&seed)?
+                } else if matches.get_flag("seed_stdin") {
+                    keypair_from_raw_seed(&read_secret("Seed: ")?)?
+                } else {
+                    let language = try_get_recovery_language(matches)?;
+                    let derivation_path = matches
+                        .get_one::<u32>("at_index")
+                        .copied()
+                        .map(bip44_account_path);
+                    keypair_from_seed_phrase(matches, language, derivation_path)?
+                };
+
+                let confirm = !matches.get_flag("no_confirm") && stdin().is_terminal();
+                if confirm && !confirm_pubkey(&keypair.pubkey())? {
+                    return Err("Recovery aborted, no keypair was written".into());
+                }
+                output_keypair(
+                    &keypair,
+                    outfile,
+                    "recovered",
+                    matches.get_flag("force"),
+                    backup,
+                    try_get_encoding(matches, KeypairEncoding::JsonArray)?,
+                    &mut stdout(),
+                )
+                .map_err(|err| format!("Unable to write {outfile}: {err}"))?;
+            }
+            ("change-passphrase", matches) => {
+                let path = try_get_keypair_path(matches, "keypair", "--keypair", &default_outfile)?;
+                let keypair = read_keypair_from(path)?;
+                let language = try_get_recovery_language(matches)?;
+                let derivation_path = try_get_derivation_path(matches)?;
+                let mnemonic = prompt_mnemonic(language)?;
+                let current_passphrase =
+                    read_secret("Current BIP39 passphrase (empty for none): ")?;
+                let current_keypair = keypair_from_bip39_seed(
+                    &Seed::new(&mnemonic, &current_passphrase),
+                    derivation_path.clone(),
+                )?;
+                if current_keypair.pubkey() != keypair.pubkey() {
+                    return Err(format!(
+                        "The seed phrase and current passphrase do not derive the keypair in \
+                         {path}, nothing was changed"
+                    )
+                    .into());
+                }
+                let new_passphrase = prompt_passphrase("New BIP39 passphrase (empty for none): ")?;
+                eprintln!(
+                    "Warning: a BIP39 passphrase is not stored in the keypair file, so {path} \
+                     and its private key are unchanged. The seed phrase with the new \
+                     passphrase derives a different keypair, with its own pubkey and funds"
+                );
+                print!("Show the pubkey derived with the new passphrase? (y/n): ");
+                stdout().flush()?;
+                let mut input = String::new();
+                stdin().read_line(&mut input)?;
+                if input.trim().eq_ignore_ascii_case("y") {
+                    let new_keypair = keypair_from_bip39_seed(
+                        &Seed::new(&mnemonic, &new_passphrase),
+                        derivation_path,
+                    )?;
+                    println!("pubkey with the new passphrase: {}", new_keypair.pubkey());
+                }
+            }
+            ("rotate", matches) => {
+                let outfile = matches.get_one::<String>("outfile").unwrap().as_str();
+                let manifest = matches
+                    .get_one::<String>("manifest")
+                    .cloned()
+                    .unwrap_or_else(|| format!("{outfile}.lineage.json"));
+                check_outfile_path(outfile, "outfile", matches)?;
+                check_for_overwrite(outfile, matches)?;
+                check_outfile_path(&manifest, "manifest", matches)?;
+                check_for_overwrite(&manifest, matches)?;
+
+                let from = matches.get_one::<String>("from").unwrap();
+                let old_keypair = read_keypair_from(from)?;
+                let from_derivation_path = matches
+                    .get_one::<String>("from_derivation_path")
+                    .map(|path| parse_derivation_path(path))
+                    .transpose()?;
+                let derivation_path = try_get_derivation_path(matches)?;
+                let language = try_get_recovery_language(matches)?;
+                let seed = seed_from_seed_phrase(matches, language)?;
+                if keypair_from_bip39_seed(&seed, from_derivation_path.clone())?.pubkey()
+                    != old_keypair.pubkey()
+                {
+                    return Err(format!(
+                        "The seed phrase does not derive the keypair in {from}{}, nothing was \
+                         written",
+                        from_derivation_path
+                            .as_ref()
+                            .map(|path| format!(" along {path:?}"))
+                            .unwrap_or_default()
+                    )
+                    .into());
+                }
+                let new_keypair = keypair_from_bip39_seed(&seed, derivation_path.clone())?;
+                if new_keypair.pubkey() == old_keypair.pubkey() {
+                    return Err(
+                        "The new derivation path derives the same keypair, pick another one".into(),
                     );
                 }
+
+                output_keypair(
+                    &new_keypair,
+                    outfile,
+                    "rotated",
+                    matches.get_flag("force"),
+                    try_get_backup(matches),
+                    KeypairEncoding::JsonArray,
+                    &mut stdout(),
+                )
+                .map_err(|err| format!("Unable to write {outfile}: {err}"))?;
+                let lineage = serde_json::json!({
+                    "from": {
+                        "pubkey": old_keypair.pubkey().to_string(),
+                        "derivation_path": from_derivation_path.map(|path| format!("{path:?}")),
+                    },
+                    "to": {
+                        "pubkey": new_keypair.pubkey().to_string(),
+                        "derivation_path": derivation_path.map(|path| format!("{path:?}")),
+                    },
+                    "rotated_at": SystemTime::now().duration_since(UNIX_EPOCH)?.as_secs(),
+                });
+                fs::write(&manifest, format!("{lineage:#}\n"))
+                    .map_err(|err| format!("Unable to write {manifest}: {err}"))?;
+                println!("Wrote lineage manifest to {manifest}");
+                println!("{} -> {}", old_keypair.pubkey(), new_keypair.pubkey());
+            }
+            ("pubkey", matches) => {
+                let pubkey = if matches.get_flag("from_seed_phrase") {
+                    let language = try_get_recovery_language(matches)?;
+                    let derivation_path = try_get_derivation_path(matches)?;
+                    keypair_from_seed_phrase(matches, language, derivation_path)?.pubkey()
+                } else {
+                    read_keypair_from(try_get_keypair_path(
+                        matches,
+                        "keypair",
+                        "the KEYPAIR argument",
+                        &default_outfile,
+                    )?)?
+                    .pubkey()
+                };
+                let encoded_pubkey = encode_pubkey(
+                    &pubkey,
+                    matches.get_one::<String>(PUBKEY_ENCODING_ARG.name).unwrap(),
+                );
+                println!("{encoded_pubkey}");
+                if matches.get_flag("copy") {
+                    // The pubkey is already printed, so a missing clipboard is not fatal.
+                    match copy_to_clipboard(&encoded_pubkey) {
+                        Ok(()) => eprintln!("Copied pubkey to the clipboard"),
+                        Err(err) => eprintln!("Unable to copy pubkey to the clipboard: {err}"),
+                    }
+                }
+                if matches.get_flag(QR_ARG.name) {
+                    let columns = terminal_size_of(stdout()).map(|(Width(columns), _)| columns);
+                    println!("{}", pubkey_qr_code(&pubkey, columns)?);
+                }
+            }
+            ("verify", matches) => {
+                let pubkey = *matches.get_one::<Pubkey>("pubkey").unwrap();
+                let path = try_get_keypair_path(
+                    matches,
+                    "keypair",
+                    "the KEYPAIR argument",
+                    &default_outfile,
+                )?;
+                let (verified, exit_code) = match read_keypair_from(path) {
+                    Ok(keypair) if verify_keypair(&keypair, &pubkey) => (true, 0),
+                    Ok(_) => (false, VERIFY_MISMATCH_EXIT_CODE),
+                    Err(err) => {
+                        eprintln!("{err}");
+                        (false, VERIFY_UNREADABLE_EXIT_CODE)
+                    }
+                };
+                match matches.get_one::<String>("output").unwrap().as_str() {
+                    "display" => println!(
+                        "Verification for public key: {pubkey}: {}",
+                        if verified { "Success" } else { "Failed" }
+                    ),
+                    "json" => println!(
+                        "{}",
+                        serde_json::json!({ "verified": verified, "pubkey": pubkey.to_string() })
+                    ),
+                    _ => unreachable!(),
+                }
+                process::exit(exit_code);
+            }
+            ("sign-message", matches) => {
+                let message = read_message(matches)?;
+                let keypair = read_keypair_from(try_get_keypair_path(
+                    matches,
+                    "keypair",
+                    "the KEYPAIR argument",
+                    &default_outfile,
+                )?)?;
+                let signature = sign_domain_message(&keypair, &message);
+                match matches.get_one::<String>("output").unwrap().as_str() {
+                    "display" => println!("{signature}"),
+                    "json" => println!(
+                        "{}",
+                        serde_json::json!({
+                            "pubkey": keypair.pubkey().to_string(),
+                            "signature": signature.to_string(),
+                        })
+                    ),
+                    _ => unreachable!(),
+                }
+            }
+            ("verify-message", matches) => {
+                let pubkey = *matches.get_one::<Pubkey>("pubkey").unwrap();
+                let signature = matches.get_one::<Signature>("signature").unwrap();
+                let message = read_message(matches)?;
+                let verified = verify_domain_message(&pubkey, &message, signature);
+                match matches.get_one::<String>("output").unwrap().as_str() {
+                    "display" => println!(
+                        "Message signature verification for public key: {pubkey}: {}",
+                        if verified { "Success" } else { "Failed" }
+                    ),
+                    "json" => println!(
+                        "{}",
+                        serde_json::json!({ "verified": verified, "pubkey": pubkey.to_string() })
+                    ),
+                    _ => unreachable!(),
+                }
+                process::exit(if verified { 0 } else { 1 });
+            }
+            ("convert", matches) => {
+                let from = matches.get_one::<String>("from").unwrap().as_str();
+                let to = matches.get_one::<String>("to").unwrap().as_str();
+                let encoding = match matches.get_one::<String>("output_format").unwrap().as_str() {
+                    "json-array" => KeypairEncoding::JsonArray,
+                    "base58" => KeypairEncoding::Base58,
+                    _ => unreachable!(),
+                };
+
+                let keypair = read_keypair_from(from)?;
+                if to != STDOUT_OUTFILE_TOKEN {
+                    check_outfile_path(to, "to", matches)?;
+                    check_for_overwrite(to, matches)?;
+                }
+                output_keypair(
+                    &keypair,
+                    to,
+                    "converted",
+                    matches.get_flag("force"),
+                    try_get_backup(matches),
+                    try_get_encoding(matches, encoding)?,
+                    &mut stdout(),
+                )
+                .map_err(|err| format!("Unable to write {to}: {err}"))?;
+            }
+            ("change-encryption", matches) => {
+                let path = try_get_keypair_path(
+                    matches,
+                    "keypair",
+                    "the KEYPAIR argument",
+                    &default_outfile,
+                )?;
+                check_rewritable_keypair_path(path)?;
+                let keypair = read_keypair_from(path)?;
+                let password = prompt_encryption_password()?;
+                output_keypair(
+                    &keypair,
+                    path,
+                    "encrypted",
+                    true,
+                    false,
+                    KeypairEncoding::Encrypted(password),
+                    &mut stdout(),
+                )
+                .map_err(|err| format!("Unable to write {path}: {err}"))?;
+            }
+            ("decrypt", matches) => {
+                let path = try_get_keypair_path(
+                    matches,
+                    "keypair",
+                    "the KEYPAIR argument",
+                    &default_outfile,
+                )?;
+                let outfile = matches.get_one::<String>("outfile").unwrap().as_str();
+                let keypair = read_keypair_from(path)?;
+                if outfile != STDOUT_OUTFILE_TOKEN {
+                    check_outfile_path(outfile, "outfile", matches)?;
+                    check_for_overwrite(outfile, matches)?;
+                }
+                output_keypair(
+                    &keypair,
+                    outfile,
+                    "decrypted",
+                    matches.get_flag("force"),
+                    try_get_backup(matches),
+                    KeypairEncoding::JsonArray,
+                    &mut stdout(),
+                )
+                .map_err(|err| format!("Unable to write {outfile}: {err}"))?;
+            }
+            ("inspect", matches) => {
+                let path = try_get_keypair_path(matches, "keypair", "--keypair", &default_outfile)?;
+                let metadata =
+                    fs::metadata(path).map_err(|err| format!("Unable to read {path}: {err}"))?;
+                let contents = Zeroizing::new(
+                    fs::read_to_string(path)
+                        .map_err(|err| format!("Unable to read {path}: {err}"))?,
+                );
+                let format = keypair_format_name(&contents);
+                let keypair = read_keypair_any_format(&mut contents.as_bytes(), false)
+                    .map_err(|err| format!("Unable to read {path}: {err}"))?;
+                let full_path = fs::canonicalize(path).unwrap_or_else(|_| PathBuf::from(path));
+                println!("Pubkey: {}", keypair.pubkey());
+                println!("Path: {}", full_path.display());
+                println!("Format: {format}");
+                println!(
+                    "Encrypted: {}",
+                    if format == ENCRYPTED_KEYPAIR_FORMAT {
+                        "yes"
+                    } else {
+                        "no"
+                    }
+                );
+                println!("Created: {}", file_time(metadata.created()));
+                println!("Modified: {}", file_time(metadata.modified()));
+                #[cfg(unix)]
+                {
+                    use std::os::unix::fs::PermissionsExt;
+                    let mode = metadata.permissions().mode() & 0o777;
+                    println!("Permissions: {mode:o}");
+                    if mode & 0o004 != 0 {
+                        eprintln!(
+                            "Warning: {path} is readable by every user on this system, restrict \
+                             it with chmod 600"
+                        );
+                    }
+                }
+            }
+            ("validate-phrase", matches) => {
+                let language_name = matches.get_one::<String>("language").unwrap();
+                let language = try_get_language(matches)?.unwrap();
+                let seed_phrase = match matches.get_one::<String>("phrase") {
+                    Some(phrase) => Zeroizing::new(phrase.clone()),
+                    None => prompt_seed_phrase("Seed phrase: ")?,
+                };
+                let word_count = validate_seed_phrase(&seed_phrase, language)?;
+                println!("Seed phrase is valid: {word_count} words, {language_name}");
+            }
+            ("list-languages", _) => print_languages(),
+            ("completions", matches) => {
+                let shell = *matches.get_one::<Shell>("shell").unwrap();
+                let mut command = app(default_outfile.ok());
+                let name = command.get_name().to_string();
+                generate(shell, &mut command, name, &mut stdout());
             }
             _ => unreachable!(),
         }
@@ -117,24 +1214,268 @@ fn main() -> Result<(), Box<dyn error::Error>> {
     Ok(())
 }
 
+/// Loads the config file given with --config, falling back to the default CLI config file
+/// when one exists.
+fn load_config(config_file: Option<&str>) -> Result<Option<Config>, Box<dyn error::Error>> {
+    if let Some(config_file) = config_file {
+        let config = Config::load(config_file)
+            .map_err(|err| format!("Unable to load config file {config_file}: {err}"))?;
+        return Ok(Some(config));
+    }
+    Ok(solana_cli_config::CONFIG_FILE
+        .as_ref()
+        .filter(|config_file| Path::new(config_file).exists())
+        .and_then(|config_file| Config::load(config_file).ok()))
+}
+
+fn default_keypair_path() -> Result<String, Box<dyn error::Error>> {
+    let mut path = home_dir()?;
+    path.extend([".config", "blockchain", "id.json"]);
+    path.into_os_string()
+        .into_string()
+        .map_err(|path| format!("Default keypair path {path:?} is not valid UTF-8").into())
+}
+
+/// Returns the keypair path given to `arg`. When it was left out and there is no default
+/// keypair path to fall back on, the error explains why and asks for `hint` instead.
+fn try_get_keypair_path<'a>(
+    matches: &'a ArgMatches,
+    arg: &str,
+    hint: &str,
+    default_outfile: &'a Result<String, String>,
+) -> Result<&'a str, Box<dyn error::Error>> {
+    match (matches.get_one::<String>(arg), default_outfile) {
+        (Some(path), _) => Ok(path),
+        (None, Ok(default_outfile)) => Ok(default_outfile),
+        (None, Err(err)) => Err(format!(
+            "{err}, and no config file was found, so there is no default keypair path. Pass \
+             {hint}, or a config file with a keypair_path using --config"
+        )
+        .into()),
+    }
+}
+
+/// Resolves the home directory from `$HOME`, falling back to `%USERPROFILE%` for Windows.
+fn home_dir() -> Result<PathBuf, Box<dyn error::Error>> {
+    ["HOME", "USERPROFILE"]
+        .into_iter()
+        .filter_map(env::var_os)
+        .find(|home| !home.is_empty())
+        .map(PathBuf::from)
+        .ok_or_else(|| {
+            "Unable to determine the home directory, neither HOME nor USERPROFILE is set".into()
+        })
+}
+
+const MESSAGE_FILE_ARG: ArgConstant<'static> = ArgConstant {
+    long: "message-file",
+    name: "message_file",
+    help: "Treat MESSAGE as the path of a file and use its raw bytes as the message, for \
+           binary payloads",
+};
+
+fn message_file_arg() -> Arg {
+    Arg::new(MESSAGE_FILE_ARG.name)
+        .long(MESSAGE_FILE_ARG.long)
+        .action(ArgAction::SetTrue)
+        .help(MESSAGE_FILE_ARG.help)
+}
+
+/// Returns the bytes of the MESSAGE argument, or of the file it names with --message-file.
+fn read_message(matches: &ArgMatches) -> Result<Vec<u8>, Box<dyn error::Error>> {
+    let message = matches.get_one::<String>("message").unwrap();
+    if matches.get_flag(MESSAGE_FILE_ARG.name) {
+        Ok(fs::read(message).map_err(|err| format!("Unable to read {message}: {err}"))?)
+    } else {
+        Ok(message.clone().into_bytes())
+    }
+}
+
+// Exit codes of `verify` for a keypair that cannot sign for the pubkey and one that cannot be read.
+const VERIFY_MISMATCH_EXIT_CODE: i32 = 2;
+const VERIFY_UNREADABLE_EXIT_CODE: i32 = 3;
+
 // Sentinel value used to indicate to write to screen instead of file
 pub const STDOUT_OUTFILE_TOKEN: &str = "-";
 
+// Prefix of a keypair source naming the environment variable that holds the keypair
+const ENV_KEYPAIR_PREFIX: &str = "env:";
+
+enum KeypairEncoding {
+    /// JSON array of the 64 keypair bytes, the format of keypair files.
+    JsonArray,
+    /// Base58 string of the 64 keypair bytes, as exported by wallets.
+    Base58,
+    /// JSON envelope of the keypair bytes encrypted with the given password.
+    Encrypted(Zeroizing<String>),
+}
+
+#[cfg(feature = "clipboard")]
+fn copy_to_clipboard(text: &str) -> Result<(), String> {
+    arboard::Clipboard::new()
+        .and_then(|mut clipboard| clipboard.set_text(text))
+        .map_err(|err| format!("no clipboard is available: {err}"))
+}
+
+#[cfg(not(feature = "clipboard"))]
+fn copy_to_clipboard(_text: &str) -> Result<(), String> {
+    Err("clipboard support is not built in, rebuild with --features clipboard".to_string())
+}
+
+/// Formats a file timestamp as RFC 3339, for filesystems that do not record it "unavailable".
+fn file_time(time: io::Result<SystemTime>) -> String {
+    time.map(|time| DateTime::<Utc>::from(time).to_rfc3339_opts(SecondsFormat::Secs, true))
+        .unwrap_or_else(|_| "unavailable".to_string())
+}
+
+/// Reads a keypair in any supported format from `path`, from stdin when `path` is "-", or
+/// from an environment variable when `path` is `env:VAR_NAME`.
+fn read_keypair_from(path: &str) -> Result<Keypair, Box<dyn error::Error>> {
+    if is_signer_uri(path) {
+        let signer = parse_signer_uri(path)?;
+        return Err(format!(
+            "hardware wallet signers are not supported, cannot use the {signer} given by {path}"
+        )
+        .into());
+    }
+    if path == STDOUT_OUTFILE_TOKEN {
+        read_keypair_any_format(&mut stdin(), true)
+    } else if let Some(var_name) = path.strip_prefix(ENV_KEYPAIR_PREFIX) {
+        // Errors must only name the variable, never echo its value.
+        env::var(var_name)
+            .map(Zeroizing::new)
+            .map_err(|_| {
+                format!("environment variable {var_name} is not set or not valid UTF-8").into()
+            })
+            .and_then(|value| read_keypair_any_format(&mut value.as_bytes(), false))
+    } else {
+        File::open(path)
+            .map_err(|err| err.into())
+            .and_then(|mut file| read_keypair_any_format(&mut file, false))
+    }
+    .map_err(|err| format!("Unable to read {path}: {err}").into())
+}
+
+/// Rejects keypair sources that cannot be rewritten in place, such as stdin or an
+/// environment variable.
+fn check_rewritable_keypair_path(path: &str) -> Result<(), String> {
+    if path == STDOUT_OUTFILE_TOKEN || path.starts_with(ENV_KEYPAIR_PREFIX) {
+        return Err(format!(
+            "{path} cannot be rewritten in place, KEYPAIR must be a keypair file"
+        ));
+    }
+    Ok(())
+}
+
 fn output_keypair(
     keypair: &Keypair,
     outfile: &str,
     source: &str,
+    overwrite: bool,
+    backup: bool,
+    encoding: KeypairEncoding,
+    status: &mut dyn Write,
 ) -> Result<(), Box<dyn error::Error>> {
+    // Encode first so that a failure cannot leave a truncated file behind.
+    let encoded = encode_keypair(keypair, encoding)?;
     if outfile == STDOUT_OUTFILE_TOKEN {
-        let mut stdout = std::io::stdout();
-        write_keypair(keypair, &mut stdout)?;
+        stdout().write_all(encoded.as_bytes())?;
     } else {
-        write_keypair_file(keypair, outfile)?;
-        println!("Wrote {source} keypair to {outfile}");
+        if (backup || overwrite) && fs::symlink_metadata(outfile).is_ok() {
+            // The new keypair is fully written and synced before it is renamed over the old
+            // one, so an interrupted run still leaves a usable keypair file behind. The old one
+            // is moved rather than copied so the backup keeps the original permissions.
+            let staged = format!("{outfile}.new-{}", process::id());
+            let mut file = create_keypair_file(&staged, false)?;
+            file.write_all(encoded.as_bytes())?;
+            file.sync_all()?;
+            let backup_path = if backup {
+                Some(backup_keypair_file(outfile).inspect_err(|_| {
+                    let _ = fs::remove_file(&staged);
+                })?)
+            } else {
+                None
+            };
+            fs::rename(&staged, outfile).map_err(|err| match &backup_path {
+                Some(backup_path) => format!(
+                    "{err}, the new keypair was left at {staged} and the old one at {backup_path}"
+                ),
+                None => {
+                    let _ = fs::remove_file(&staged);
+                    err.to_string()
+                }
+            })?;
+            if let Some(backup_path) = backup_path {
+                writeln!(status, "Backed up existing keypair to {backup_path}")?;
+            }
+        } else {
+            let mut file = create_keypair_file(outfile, overwrite)?;
+            file.write_all(encoded.as_bytes())?;
+        }
+        writeln!(status, "Wrote {source} keypair to {outfile}")?;
     }
     Ok(())
 }
 
+fn encode_keypair(
+    keypair: &Keypair,
+    encoding: KeypairEncoding,
+) -> Result<Zeroizing<String>, Box<dyn error::Error>> {
+    Ok(match encoding {
+        KeypairEncoding::JsonArray => keypair_json(keypair),
+        KeypairEncoding::Base58 => {
+            let mut base58 = Zeroizing::new(keypair.to_base58_string());
+            base58.push('\n');
+            base58
+        }
+        KeypairEncoding::Encrypted(password) => {
+            Zeroizing::new(encrypt_keypair(keypair, &password)?)
+        }
+    })
+}
+
+/// Encodes the keypair bytes as a JSON array, wiping every intermediate buffer on drop.
+fn keypair_json(keypair: &Keypair) -> Zeroizing<String> {
+    use std::fmt::Write as _;
+
+    let bytes = Zeroizing::new(keypair.to_bytes());
+    let mut json = Zeroizing::new(String::with_capacity(bytes.len() * 4 + 2));
+    json.push('[');
+    for (i, byte) in bytes.iter().enumerate() {
+        if i > 0 {
+            json.push(',');
+        }
+        write!(json, "{byte}").unwrap();
+    }
+    json.push(']');
+    json
+}
+
+/// Creates the keypair file readable only by its owner. Unless `overwrite` is set, the file
+/// is created with create-new semantics, so nothing that appears at `outfile` after
+/// `check_for_overwrite` ran, including a symlink, can be overwritten.
+fn create_keypair_file(outfile: &str, overwrite: bool) -> Result<File, Box<dyn error::Error>> {
+    let mut options = OpenOptions::new();
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::OpenOptionsExt;
+        options.mode(0o600);
+    }
+    options.write(true);
+    if overwrite {
+        options.create(true).truncate(true);
+    } else {
+        options.create_new(true);
+    }
+    let file = options.open(outfile).map_err(|err| match err.kind() {
+        io::ErrorKind::AlreadyExists => {
+            format!("Refusing to overwrite {outfile} without --force flag")
+        }
+        _ => err.to_string(),
+    })?;
+    Ok(file)
+}
+
 pub(crate) struct ArgConstant<'a> {
     pub long: &'a str,
     pub name: &'a str,
@@ -147,6 +1488,102 @@ const NO_OUTFILE_ARG: ArgConstant<'static> = ArgConstant {
     help: "Only print a seed phrase and pubkey. Do not output a keypair file",
 };
 
+const ENCRYPT_ARG: ArgConstant<'static> = ArgConstant {
+    long: "encrypt",
+    name: "encrypt",
+    help: "Encrypt the keypair file with a password that will be prompted for",
+};
+
+fn encrypt_arg() -> Arg {
+    Arg::new(ENCRYPT_ARG.name)
+        .long(ENCRYPT_ARG.long)
+        .action(ArgAction::SetTrue)
+        .help(ENCRYPT_ARG.help)
+}
+
+/// Returns the encrypted encoding, prompting for its password, when --encrypt is given and
+/// `plain` otherwise.
+fn try_get_encoding(
+    matches: &ArgMatches,
+    plain: KeypairEncoding,
+) -> Result<KeypairEncoding, Box<dyn error::Error>> {
+    if matches.get_flag(ENCRYPT_ARG.name) {
+        Ok(KeypairEncoding::Encrypted(prompt_encryption_password()?))
+    } else {
+        Ok(plain)
+    }
+}
+
+const PUBKEY_ENCODING_ARG: ArgConstant<'static> = ArgConstant {
+    long: "encoding",
+    name: "pubkey_encoding",
+    help: "Encoding of the printed pubkey: base58, or the raw 32 bytes as hex or base64",
+};
+
+fn pubkey_encoding_arg() -> Arg {
+    Arg::new(PUBKEY_ENCODING_ARG.name)
+        .long(PUBKEY_ENCODING_ARG.long)
+        .value_name("ENCODING")
+        .value_parser(["base58", "hex", "base64"])
+        .default_value("base58")
+        .help(PUBKEY_ENCODING_ARG.help)
+}
+
+const QR_ARG: ArgConstant<'static> = ArgConstant {
+    long: "qr",
+    name: "qr",
+    help: "Also show the base58 pubkey as a QR code, to scan it into a phone wallet",
+};
+
+fn qr_arg() -> Arg {
+    Arg::new(QR_ARG.name)
+        .long(QR_ARG.long)
+        .action(ArgAction::SetTrue)
+        .help(QR_ARG.help)
+}
+
+/// Renders the base58 `pubkey` as a QR code of unicode half blocks, light modules on the
+/// dark background of a terminal. Fails when the terminal it is shown on is known to be fewer
+/// than `columns` wide.
+fn pubkey_qr_code(pubkey: &Pubkey, columns: Option<u16>) -> Result<String, String> {
+    let qr_code = QrCode::new(pubkey.to_string())
+        .map_err(|err| format!("Unable to encode the pubkey as a QR code: {err}"))?
+        .render::<Dense1x2>()
+        .dark_color(Dense1x2::Light)
+        .light_color(Dense1x2::Dark)
+        .build();
+    let width = qr_code.lines().map(|line| line.chars().count()).max();
+    if let (Some(columns), Some(width)) = (columns, width)
+        && width > usize::from(columns)
+    {
+        return Err(format!(
+            "The terminal is {columns} columns wide, too narrow for the {width} column QR code \
+             of the pubkey"
+        ));
+    }
+    Ok(qr_code)
+}
+
+const NO_BACKUP_ARG: ArgConstant<'static> = ArgConstant {
+    long: "no-backup",
+    name: "no_backup",
+    help: "With --force, replace an existing output file without first moving it to \
+           <FILEPATH>.bak-<TIMESTAMP>",
+};
+
+fn no_backup_arg() -> Arg {
+    Arg::new(NO_BACKUP_ARG.name)
+        .long(NO_BACKUP_ARG.long)
+        .action(ArgAction::SetTrue)
+        .help(NO_BACKUP_ARG.help)
+}
+
+/// Returns whether an existing output file is moved aside before it is replaced, which
+/// --force does unless --no-backup is given.
+fn try_get_backup(matches: &ArgMatches) -> bool {
+    matches.get_flag("force") && !matches.get_flag(NO_BACKUP_ARG.name)
+}
+
 trait KeyGenerationCommonArgs {
     fn key_generation_common_args(self) -> Self;
 }
@@ -159,14 +1596,467 @@ impl KeyGenerationCommonArgs for Command {
     }
 }
 
+const CREATE_DIRS_ARG: ArgConstant<'static> = ArgConstant {
+    long: "create-dirs",
+    name: "create_dirs",
+    help: "Create the missing parent directories of the output file. Always done for the \
+           default keypair path",
+};
+
+fn create_dirs_arg() -> Arg {
+    Arg::new(CREATE_DIRS_ARG.name)
+        .long(CREATE_DIRS_ARG.long)
+        .action(ArgAction::SetTrue)
+        .help(CREATE_DIRS_ARG.help)
+}
+
+/// Checks that the `outfile` given to the `arg` argument can be written as a file. It must
+/// not be a directory, and its missing parent directories are only created with
+/// --create-dirs or when `outfile` is the default keypair path.
+fn check_outfile_path(
+    outfile: &str,
+    arg: &str,
+    matches: &ArgMatches,
+) -> Result<(), Box<dyn error::Error>> {
+    let path = Path::new(outfile);
+    if path.is_dir() {
+        return Err(format!(
+            "{outfile} is a directory, give the path of the keypair file to write instead, \
+             such as {}",
+            path.join("id.json").display()
+        )
+        .into());
+    }
+    let Some(parent) = path
+        .parent()
+        .filter(|parent| !parent.as_os_str().is_empty())
+    else {
+        return Ok(());
+    };
+    if parent.is_dir() {
+        return Ok(());
+    }
+    if parent.exists() {
+        return Err(format!("{} is not a directory", parent.display()).into());
+    }
+    if matches.get_flag(CREATE_DIRS_ARG.name)
+        || matches.value_source(arg) == Some(ValueSource::DefaultValue)
+    {
+        fs::create_dir_all(parent)
+            .map_err(|err| format!("Unable to create directory {}: {err}", parent.display()))?;
+        Ok(())
+    } else {
+        Err(format!(
+            "Directory {} does not exist, create it or pass --{}",
+            parent.display(),
+            CREATE_DIRS_ARG.long
+        )
+        .into())
+    }
+}
+
 pub fn check_for_overwrite(
     outfile: &str,
     matches: &ArgMatches,
 ) -> Result<(), Box<dyn error::Error>> {
     let force = matches.get_flag("force");
-    if !force && Path::new(outfile).exists() {
-        let err_msg = format!("Refusing to overwrite {outfile} without --force flag");
-        return Err(err_msg.into());
+    if force {
+        return Ok(());
+    }
+    // Unlike `Path::exists`, this does not follow symlinks, so dangling ones are caught too.
+    match fs::symlink_metadata(outfile) {
+        Ok(metadata) if metadata.file_type().is_symlink() => {
+            let target = fs::canonicalize(outfile)
+                .or_else(|_| fs::read_link(outfile))
+                .map(|target| target.display().to_string())
+                .unwrap_or_default();
+            Err(format!(
+                "Refusing to write through symlink {outfile} -> {target} without --force flag"
+            )
+            .into())
+        }
+        Ok(_) => Err(format!("Refusing to overwrite {outfile} without --force flag").into()),
+        Err(err) if err.kind() == io::ErrorKind::NotFound => Ok(()),
+        Err(err) => Err(format!("Unable to check {outfile}: {err}").into()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use solana_keypair::read_keypair;
+    use std::collections::HashSet;
+    use std::ffi::OsString;
+    use std::panic;
+    use std::sync::Mutex;
+    use tempfile::TempDir;
+
+    // Held by tests that change environment variables, as the environment is process wide.
+    static ENV_LOCK: Mutex<()> = Mutex::new(());
+
+    #[test]
+    fn test_read_keypair_from_env() {
+        let _lock = ENV_LOCK.lock().unwrap();
+        let keypair = Keypair::new();
+        let var_name = "SOLARIUM_KEYGEN_TEST_KEYPAIR";
+        let path = format!("env:{var_name}");
+
+        for value in [
+            keypair_json(&keypair),
+            Zeroizing::new(keypair.to_base58_string()),
+        ] {
+            unsafe { env::set_var(var_name, value.as_str()) };
+            assert_eq!(
+                read_keypair_from(&path).unwrap().to_bytes(),
+                keypair.to_bytes()
+            );
+        }
+
+        let secret = "not-a-keypair-secret";
+        unsafe { env::set_var(var_name, secret) };
+        let err = read_keypair_from(&path).unwrap_err().to_string();
+        assert!(
+            err.starts_with(&format!("Unable to read {path}: ")),
+            "{err}"
+        );
+        assert!(!err.contains(secret), "{err}");
+
+        unsafe { env::remove_var(var_name) };
+        assert_eq!(
+            read_keypair_from(&path).unwrap_err().to_string(),
+            format!(
+                "Unable to read {path}: environment variable {var_name} is not set or not valid \
+                 UTF-8"
+            )
+        );
+    }
+
+    /// Runs `f` with HOME and USERPROFILE set to `home` and `user_profile`, or unset for
+    /// `None`, restoring both afterwards.
+    fn with_home_vars(home: Option<&str>, user_profile: Option<&str>, f: impl FnOnce()) {
+        let _lock = ENV_LOCK.lock().unwrap();
+        let set_vars = |vars: [(&str, Option<OsString>); 2]| {
+            for (name, value) in vars {
+                match value {
+                    Some(value) => unsafe { env::set_var(name, value) },
+                    None => unsafe { env::remove_var(name) },
+                }
+            }
+        };
+        let saved = [
+            ("HOME", env::var_os("HOME")),
+            ("USERPROFILE", env::var_os("USERPROFILE")),
+        ];
+        set_vars([
+            ("HOME", home.map(OsString::from)),
+            ("USERPROFILE", user_profile.map(OsString::from)),
+        ]);
+        let result = panic::catch_unwind(panic::AssertUnwindSafe(f));
+        set_vars(saved);
+        if let Err(err) = result {
+            panic::resume_unwind(err);
+        }
+    }
+
+    #[test]
+    fn test_default_keypair_path() {
+        let id_json = |home: &str| {
+            let path = Path::new(home).join(".config/blockchain/id.json");
+            path.to_str().unwrap().to_string()
+        };
+        with_home_vars(Some("/home/keygen"), Some("/home/profile"), || {
+            assert_eq!(default_keypair_path().unwrap(), id_json("/home/keygen"));
+        });
+        // An empty HOME falls back on USERPROFILE, as on Windows.
+        with_home_vars(Some(""), Some("/home/profile"), || {
+            assert_eq!(default_keypair_path().unwrap(), id_json("/home/profile"));
+        });
+        with_home_vars(None, Some("/home/profile"), || {
+            assert_eq!(default_keypair_path().unwrap(), id_json("/home/profile"));
+        });
+    }
+
+    #[test]
+    fn test_no_home_dir() {
+        with_home_vars(None, None, || {
+            let default_outfile = default_keypair_path().map_err(|err| err.to_string());
+            assert_eq!(
+                default_outfile,
+                Err(
+                    "Unable to determine the home directory, neither HOME nor USERPROFILE is set"
+                        .to_string()
+                )
+            );
+
+            // Without a default keypair path the outfile is simply left unset, and asked for.
+            let matches = app(default_outfile.clone().ok())
+                .try_get_matches_from(["solarium-keygen", "new"])
+                .unwrap();
+            let matches = matches.subcommand_matches("new").unwrap();
+            assert_eq!(matches.get_one::<String>("outfile"), None);
+            assert_eq!(
+                try_get_keypair_path(matches, "outfile", "--outfile", &default_outfile)
+                    .unwrap_err()
+                    .to_string(),
+                "Unable to determine the home directory, neither HOME nor USERPROFILE is set, \
+                 and no config file was found, so there is no default keypair path. Pass \
+                 --outfile, or a config file with a keypair_path using --config"
+            );
+
+            // A keypair_path from the config file still serves as the default.
+            let default_outfile = Ok("config.json".to_string());
+            let matches = app(default_outfile.clone().ok())
+                .try_get_matches_from(["solarium-keygen", "new"])
+                .unwrap();
+            let matches = matches.subcommand_matches("new").unwrap();
+            assert_eq!(
+                try_get_keypair_path(matches, "outfile", "--outfile", &default_outfile).unwrap(),
+                "config.json"
+            );
+        });
+    }
+
+    #[test]
+    fn test_check_rewritable_keypair_path() {
+        assert!(check_rewritable_keypair_path("id.json").is_ok());
+        assert!(check_rewritable_keypair_path(STDOUT_OUTFILE_TOKEN).is_err());
+        assert!(check_rewritable_keypair_path("env:KEYPAIR").is_err());
+    }
+
+    #[test]
+    fn test_output_keypair_replaces_existing_file() {
+        let dir = TempDir::new().unwrap();
+        let outfile = dir.path().join("id.json");
+        let outfile = outfile.to_str().unwrap();
+        let old = Keypair::new();
+        let new = Keypair::new();
+        fs::write(outfile, keypair_json(&old).as_bytes()).unwrap();
+
+        let mut status = vec![];
+        output_keypair(
+            &new,
+            outfile,
+            "test",
+            true,
+            false,
+            KeypairEncoding::JsonArray,
+            &mut status,
+        )
+        .unwrap();
+
+        assert_eq!(fs::read_to_string(outfile).unwrap(), *keypair_json(&new));
+        assert_eq!(fs::read_dir(dir.path()).unwrap().count(), 1);
+        assert_eq!(
+            String::from_utf8(status).unwrap(),
+            format!("Wrote test keypair to {outfile}\n")
+        );
+    }
+
+    #[test]
+    fn test_output_keypair_backs_up_existing_file() {
+        let dir = TempDir::new().unwrap();
+        let outfile = dir.path().join("id.json");
+        let outfile = outfile.to_str().unwrap();
+        let old = Keypair::new();
+        fs::write(outfile, keypair_json(&old).as_bytes()).unwrap();
+
+        let mut status = vec![];
+        output_keypair(
+            &Keypair::new(),
+            outfile,
+            "test",
+            true,
+            true,
+            KeypairEncoding::JsonArray,
+            &mut status,
+        )
+        .unwrap();
+
+        let backups: Vec<_> = fs::read_dir(dir.path())
+            .unwrap()
+            .map(|entry| entry.unwrap().path())
+            .filter(|path| path.to_str().unwrap() != outfile)
+            .collect();
+        assert_eq!(backups.len(), 1);
+        assert_eq!(
+            fs::read_to_string(&backups[0]).unwrap(),
+            *keypair_json(&old)
+        );
+    }
+
+    #[test]
+    fn test_keypair_json() {
+        let keypair = Keypair::new();
+        let json = keypair_json(&keypair);
+        assert_eq!(
+            *json,
+            serde_json::to_string(&keypair.to_bytes().to_vec()).unwrap()
+        );
+        assert_eq!(
+            read_keypair(&mut json.as_bytes()).unwrap().to_bytes(),
+            keypair.to_bytes()
+        );
+    }
+
+    #[cfg(not(feature = "clipboard"))]
+    #[test]
+    fn test_copy_to_clipboard_without_feature() {
+        assert_eq!(
+            copy_to_clipboard("pubkey"),
+            Err("clipboard support is not built in, rebuild with --features clipboard".to_string())
+        );
+    }
+
+    #[test]
+    fn test_file_time() {
+        assert_eq!(file_time(Ok(UNIX_EPOCH)), "1970-01-01T00:00:00Z");
+        assert_eq!(
+            file_time(Err(io::Error::from(io::ErrorKind::Unsupported))),
+            "unavailable"
+        );
+    }
+
+    /// The matches of the keygen subcommand and arguments in `args`.
+    fn subcommand_matches(args: &[&str]) -> ArgMatches {
+        let matches = app(Some("id.json".to_string()))
+            .try_get_matches_from([&["solarium-keygen"], args].concat())
+            .unwrap();
+        matches.subcommand().unwrap().1.clone()
+    }
+
+    #[test]
+    fn test_read_message() {
+        let matches = subcommand_matches(&["sign-message", "hello"]);
+        assert_eq!(read_message(&matches).unwrap(), b"hello");
+
+        let dir = TempDir::new().unwrap();
+        let path = dir.path().join("message.bin");
+        let path = path.to_str().unwrap();
+        fs::write(path, [0, 159, 146, 150]).unwrap();
+        let matches = subcommand_matches(&["sign-message", path, "--message-file"]);
+        assert_eq!(read_message(&matches).unwrap(), [0, 159, 146, 150]);
+
+        fs::remove_file(path).unwrap();
+        assert!(
+            read_message(&matches)
+                .unwrap_err()
+                .to_string()
+                .starts_with(&format!("Unable to read {path}: "))
+        );
+    }
+
+    #[test]
+    fn test_check_outfile_path() {
+        let dir = TempDir::new().unwrap();
+        let outfile = |path: &str| dir.path().join(path).to_str().unwrap().to_string();
+        let check = |outfile: &str, args: &[&str]| {
+            let matches = subcommand_matches(&[&["new", "--outfile", outfile], args].concat());
+            check_outfile_path(outfile, "outfile", &matches).map_err(|err| err.to_string())
+        };
+
+        assert_eq!(check(&outfile("id.json"), &[]), Ok(()));
+        assert_eq!(
+            check(dir.path().to_str().unwrap(), &[]),
+            Err(format!(
+                "{} is a directory, give the path of the keypair file to write instead, such as \
+                 {}",
+                dir.path().display(),
+                outfile("id.json")
+            ))
+        );
+        let nested = outfile("keys/id.json");
+        assert_eq!(
+            check(&nested, &[]),
+            Err(format!(
+                "Directory {} does not exist, create it or pass --create-dirs",
+                outfile("keys")
+            ))
+        );
+        assert_eq!(check(&nested, &["--create-dirs"]), Ok(()));
+        assert!(dir.path().join("keys").is_dir());
+
+        fs::write(outfile("file"), "").unwrap();
+        assert_eq!(
+            check(&outfile("file/id.json"), &["--create-dirs"]),
+            Err(format!("{} is not a directory", outfile("file")))
+        );
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_check_for_overwrite() {
+        let dir = TempDir::new().unwrap();
+        let path = |name: &str| dir.path().join(name).to_str().unwrap().to_string();
+        let check = |outfile: &str, args: &[&str]| {
+            let matches = subcommand_matches(&[&["new", "--outfile", outfile], args].concat());
+            check_for_overwrite(outfile, &matches).map_err(|err| err.to_string())
+        };
+
+        assert_eq!(check(&path("missing.json"), &[]), Ok(()));
+
+        fs::write(path("id.json"), "keypair").unwrap();
+        assert_eq!(
+            check(&path("id.json"), &[]),
+            Err(format!(
+                "Refusing to overwrite {} without --force flag",
+                path("id.json")
+            ))
+        );
+        assert_eq!(check(&path("id.json"), &["--force"]), Ok(()));
+
+        std::os::unix::fs::symlink(path("id.json"), path("link.json")).unwrap();
+        let target = fs::canonicalize(path("id.json")).unwrap();
+        assert_eq!(
+            check(&path("link.json"), &[]),
+            Err(format!(
+                "Refusing to write through symlink {} -> {} without --force flag",
+                path("link.json"),
+                target.display()
+            ))
+        );
+
+        std::os::unix::fs::symlink(path("missing.json"), path("dangling.json")).unwrap();
+        assert_eq!(
+            check(&path("dangling.json"), &[]),
+            Err(format!(
+                "Refusing to write through symlink {} -> {} without --force flag",
+                path("dangling.json"),
+                path("missing.json")
+            ))
+        );
+        assert_eq!(check(&path("dangling.json"), &["--force"]), Ok(()));
+        // A link that appears after the check is not followed either.
+        assert!(create_keypair_file(&path("dangling.json"), false).is_err());
+
+        // Nothing was written through either link.
+        assert_eq!(fs::read_to_string(path("id.json")).unwrap(), "keypair");
+        assert!(fs::symlink_metadata(path("missing.json")).is_err());
+    }
+
+    #[test]
+    fn test_pubkey_qr_code() {
+        let pubkey = Pubkey::new_unique();
+        let qr_code = pubkey_qr_code(&pubkey, None).unwrap();
+        let widths = qr_code
+            .lines()
+            .map(|line| line.chars().count())
+            .collect::<HashSet<_>>();
+        assert_eq!(widths.len(), 1);
+        let width = *widths.iter().next().unwrap();
+        assert!(
+            qr_code
+                .chars()
+                .all(|c| matches!(c, '█' | '▀' | '▄' | ' ' | '\n'))
+        );
+
+        assert_eq!(pubkey_qr_code(&pubkey, Some(width as u16)), Ok(qr_code));
+        assert_eq!(
+            pubkey_qr_code(&pubkey, Some(width as u16 - 1)),
+            Err(format!(
+                "The terminal is {} columns wide, too narrow for the {width} column QR code of \
+                 the pubkey",
+                width - 1
+            ))
+        );
     }
-    Ok(())
 }
@@ -1,9 +1,19 @@
 use crate::ArgConstant;
-use crate::keypair::prompt_passphrase;
-use bip39::Language;
+use crate::keypair::{decode_hex, prompt_passphrase, prompt_seed_phrase, read_secret};
+use bip39::{Language, Mnemonic, MnemonicType, Seed};
 use clap::builder::PossibleValuesParser;
 use clap::{Arg, ArgAction, ArgMatches};
+use sha2::{Digest, Sha512};
+use solana_derivation_path::DerivationPath;
+use solana_keypair::{
+    Keypair, keypair_from_seed, seed_derivable::keypair_from_seed_and_derivation_path,
+};
+use solarium_clap_utils::POSSIBLE_LANGUAGES;
+use std::cmp::Reverse;
 use std::error;
+use std::fs;
+use unicode_normalization::UnicodeNormalization;
+use zeroize::Zeroizing;
 
 pub(crate) const NO_PASSPHRASE: &str = "";
 
@@ -25,6 +35,31 @@ pub(crate) const NO_PASSPHRASE_ARG: ArgConstant<'static> = ArgConstant {
     help: "Do not prompt for a BIP39 passphrase",
 };
 
+pub(crate) const DERIVATION_PATH_ARG: ArgConstant<'static> = ArgConstant {
+    long: "derivation-path",
+    name: "derivation_path",
+    help: "Derive the keypair along this BIP44 path, either as ACCOUNT/CHANGE or as a full \
+           path like m/44'/501'/0'/0'. Without a value the default Solana path is used",
+};
+
+pub(crate) const ENTROPY_FILE_ARG: ArgConstant<'static> = ArgConstant {
+    long: "entropy-file",
+    name: "entropy_file",
+    help: "Generate the seed phrase from the SHA-512 hash of this file, such as recorded dice \
+           rolls, instead of the system randomness. The same file always gives the same seed \
+           phrase, so keep it as secret as the phrase. The file must hold at least 32 bytes",
+};
+
+pub(crate) const ENTROPY_HEX_ARG: ArgConstant<'static> = ArgConstant {
+    long: "entropy-hex",
+    name: "entropy_hex",
+    help: "Generate the seed phrase deterministically from this entropy instead of the system \
+           randomness, for test vectors. Takes 16 bytes of hex for 12 words and 32 for 24 words",
+};
+
+// Shortest entropy file accepted, so that it can matter for a 24 word seed phrase
+const MIN_ENTROPY_FILE_LEN: usize = 32;
+
 const POSSIBLE_WORD_COUNTS: &[&str] = &["12", "24"];
 
 pub(crate) fn word_count_arg() -> Arg {
@@ -48,17 +83,6 @@ pub(crate) fn try_get_word_count(
         }))
 }
 
-const POSSIBLE_LANGUAGES: &[&str] = &[
-    "english",
-    "chinese-simplified",
-    "chinese-traditional",
-    "japanese",
-    "spanish",
-    "korean",
-    "french",
-    "italian",
-];
-
 pub(crate) fn language_arg() -> Arg {
     Arg::new(LANGUAGE_ARG.name)
         .long(LANGUAGE_ARG.long)
@@ -73,17 +97,161 @@ pub(crate) fn try_get_language(
 ) -> Result<Option<Language>, Box<dyn error::Error>> {
     Ok(matches
         .try_get_one::<String>(LANGUAGE_ARG.name)?
-        .map(|language| match language.as_str() {
-            "english" => Language::English,
-            "chinese-simplified" => Language::ChineseSimplified,
-            "chinese-traditional" => Language::ChineseTraditional,
-            "japanese" => Language::Japanese,
-            "spanish" => Language::Spanish,
-            "korean" => Language::Korean,
-            "french" => Language::French,
-            "italian" => Language::Italian,
-            _ => unreachable!(),
-        }))
+        .map(|language| language_from_name(language)))
+}
+
+const AUTO_LANGUAGE: &str = "auto";
+
+/// Language argument of `recover` and the other commands reading an existing seed phrase.
+/// It defaults to "auto", detecting the language of the seed phrase.
+pub(crate) fn recovery_language_arg() -> Arg {
+    let possible_languages = [POSSIBLE_LANGUAGES, &[AUTO_LANGUAGE]].concat();
+    language_arg()
+        .value_parser(PossibleValuesParser::new(possible_languages))
+        .default_value(AUTO_LANGUAGE)
+        .help(
+            "Specify the mnemonic language of the seed phrase. With \"auto\" it is detected \
+             from the wordlist in which the phrase and its checksum are valid",
+        )
+}
+
+/// Returns the language given to `recovery_language_arg`, `None` standing for "auto".
+pub(crate) fn try_get_recovery_language(
+    matches: &ArgMatches,
+) -> Result<Option<Language>, Box<dyn error::Error>> {
+    Ok(matches
+        .try_get_one::<String>(LANGUAGE_ARG.name)?
+        .filter(|language| *language != AUTO_LANGUAGE)
+        .map(|language| language_from_name(language)))
+}
+
+/// Finds the one supported language in which `seed_phrase` is a valid mnemonic, returning
+/// its name.
+fn detect_language(seed_phrase: &str) -> Result<&'static str, String> {
+    let normalized = nfkd(seed_phrase);
+    let matching = POSSIBLE_LANGUAGES
+        .iter()
+        .filter(|name| Mnemonic::validate(&normalized, language_from_name(name)).is_ok())
+        .collect::<Vec<_>>();
+    match matching.as_slice() {
+        [name] => Ok(name),
+        [] => {
+            // Report the problems against the wordlist that knows the most words of the phrase,
+            // so that typos get a suggestion.
+            let name = POSSIBLE_LANGUAGES
+                .iter()
+                .min_by_key(|name| {
+                    let language = language_from_name(name);
+                    Reverse(
+                        seed_phrase
+                            .split_whitespace()
+                            .filter(|word| in_wordlist(word, language))
+                            .count(),
+                    )
+                })
+                .unwrap();
+            match validate_seed_phrase(seed_phrase, language_from_name(name)) {
+                Err(err) => Err(format!(
+                    "{err} (checked against {name}, the closest supported language)"
+                )),
+                Ok(_) => unreachable!("no language validates the seed phrase"),
+            }
+        }
+        names => Err(format!(
+            "Seed phrase is valid in several languages ({}), pass --{} to pick one",
+            names
+                .iter()
+                .map(|name| name.to_string())
+                .collect::<Vec<_>>()
+                .join(", "),
+            LANGUAGE_ARG.long
+        )),
+    }
+}
+
+// Names only come from POSSIBLE_LANGUAGES, directly or through clap's possible values.
+fn language_from_name(name: &str) -> Language {
+    solarium_clap_utils::language_from_name(name).unwrap()
+}
+
+/// ISO 639-1 code of the language, with a script subtag to tell the Chinese wordlists apart.
+fn language_code(language: Language) -> &'static str {
+    match language {
+        Language::English => "en",
+        Language::ChineseSimplified => "zh-Hans",
+        Language::ChineseTraditional => "zh-Hant",
+        Language::Japanese => "ja",
+        Language::Spanish => "es",
+        Language::Korean => "ko",
+        Language::French => "fr",
+        Language::Italian => "it",
+    }
+}
+
+pub(crate) fn entropy_file_arg() -> Arg {
+    Arg::new(ENTROPY_FILE_ARG.name)
+        .long(ENTROPY_FILE_ARG.long)
+        .value_name("FILEPATH")
+        .help(ENTROPY_FILE_ARG.help)
+}
+
+pub(crate) fn entropy_hex_arg() -> Arg {
+    Arg::new(ENTROPY_HEX_ARG.name)
+        .long(ENTROPY_HEX_ARG.long)
+        .value_name("HEX")
+        .help(ENTROPY_HEX_ARG.help)
+}
+
+/// Builds the mnemonic of exactly the given hex entropy, which must be as long as
+/// `mnemonic_type` requires.
+pub(crate) fn mnemonic_from_entropy_hex(
+    hex: &str,
+    mnemonic_type: MnemonicType,
+    language: Language,
+) -> Result<Mnemonic, Box<dyn error::Error>> {
+    let entropy = Zeroizing::new(decode_hex(hex.trim())?);
+    let entropy_len = mnemonic_type.entropy_bits() / 8;
+    if entropy.len() != entropy_len {
+        return Err(format!(
+            "A {} word seed phrase takes {entropy_len} bytes of entropy, found {}",
+            mnemonic_type.word_count(),
+            entropy.len()
+        )
+        .into());
+    }
+    Ok(Mnemonic::from_entropy(&entropy, language)?)
+}
+
+/// Generates a mnemonic from the SHA-512 hash of the entropy file at `path` alone, so that
+/// the same file always gives the same seed phrase.
+pub(crate) fn mnemonic_from_entropy_file(
+    path: &str,
+    mnemonic_type: MnemonicType,
+    language: Language,
+) -> Result<Mnemonic, Box<dyn error::Error>> {
+    // Errors name the file only, its contents are never shown.
+    let user_entropy = Zeroizing::new(
+        fs::read(path).map_err(|err| format!("Unable to read entropy file {path}: {err}"))?,
+    );
+    if user_entropy.len() < MIN_ENTROPY_FILE_LEN {
+        return Err(format!(
+            "Entropy file {path} holds {} bytes, at least {MIN_ENTROPY_FILE_LEN} are required",
+            user_entropy.len()
+        )
+        .into());
+    }
+    let digest = Zeroizing::new(<[u8; 64]>::from(Sha512::digest(user_entropy.as_slice())));
+    let entropy_len = mnemonic_type.entropy_bits() / 8;
+    Ok(Mnemonic::from_entropy(&digest[..entropy_len], language)?)
+}
+
+/// Prints every supported mnemonic language with its code and the first word of its wordlist.
+pub(crate) fn print_languages() {
+    for name in POSSIBLE_LANGUAGES {
+        let language = language_from_name(name);
+        let sample_word = language.wordlist().get_words_by_prefix("")[0];
+        println!("{name:<20} {:<8} {sample_word}", language_code(language));
+    }
 }
 
 pub(crate) fn no_passphrase_arg() -> Arg {
@@ -94,11 +262,21 @@ pub(crate) fn no_passphrase_arg() -> Arg {
         .action(ArgAction::SetTrue)
 }
 
+/// Prompts for the BIP39 passphrase of a new seed phrase unless --no-bip39-passphrase is
+/// given. When not `interactive`, fails instead of blocking on the prompt.
 pub(crate) fn acquire_passphrase_and_message(
     matches: &ArgMatches,
-) -> Result<(String, String), Box<dyn error::Error>> {
-    if matches.try_contains_id(NO_PASSPHRASE_ARG.name)? {
+    interactive: bool,
+) -> Result<(Zeroizing<String>, String), Box<dyn error::Error>> {
+    if matches.get_flag(NO_PASSPHRASE_ARG.name) {
         Ok(no_passphrase_and_message())
+    } else if !interactive {
+        Err(format!(
+            "cannot prompt for a BIP39 passphrase with --silent or when stdin is not a \
+             terminal, pass --{} to generate the keypair without one",
+            NO_PASSPHRASE_ARG.long
+        )
+        .into())
     } else {
         match prompt_passphrase(
             "\nFor added security, enter a BIP39 passphrase\n\
@@ -107,7 +285,7 @@ pub(crate) fn acquire_passphrase_and_message(
              \nBIP39 Passphrase (empty for none): ",
         ) {
             Ok(passphrase) => {
-                println!();
+                eprintln!();
                 Ok((passphrase, " and your BIP39 passphrase".to_string()))
             }
             Err(e) => Err(e),
@@ -115,6 +293,396 @@ pub(crate) fn acquire_passphrase_and_message(
     }
 }
 
-pub(crate) fn no_passphrase_and_message() -> (String, String) {
-    (NO_PASSPHRASE.to_string(), "".to_string())
+pub(crate) fn no_passphrase_and_message() -> (Zeroizing<String>, String) {
+    (Zeroizing::new(NO_PASSPHRASE.to_string()), "".to_string())
+}
+
+pub(crate) fn acquire_recovery_passphrase(
+    matches: &ArgMatches,
+) -> Result<Zeroizing<String>, Box<dyn error::Error>> {
+    if matches.get_flag(NO_PASSPHRASE_ARG.name) {
+        Ok(Zeroizing::new(NO_PASSPHRASE.to_string()))
+    } else {
+        Ok(read_secret(
+            "If this seed phrase has an associated passphrase, enter it now. \
+             Otherwise, press ENTER to continue: ",
+        )?)
+    }
+}
+
+/// Safe handling steps printed after a new seed phrase with `new --recovery-checklist`.
+pub(crate) const RECOVERY_CHECKLIST: &str = "\
+Recovery checklist:
+  1. Write the seed phrase down and keep it offline, somewhere only you can reach.
+  2. If you set a BIP39 passphrase, store it apart from the seed phrase. Both are needed.
+  3. Check the backup: recover it with `solarium-keygen recover -o <FILEPATH>`, then run
+     `solarium-keygen verify <PUBKEY> <FILEPATH>` and delete the recovered file.";
+
+pub(crate) fn derivation_path_arg() -> Arg {
+    Arg::new(DERIVATION_PATH_ARG.name)
+        .long(DERIVATION_PATH_ARG.long)
+        .value_name("DERIVATION_PATH")
+        .num_args(0..=1)
+        .default_missing_value("m/44'/501'/0'/0'")
+        .help(DERIVATION_PATH_ARG.help)
+}
+
+pub(crate) fn try_get_derivation_path(
+    matches: &ArgMatches,
+) -> Result<Option<DerivationPath>, Box<dyn error::Error>> {
+    matches
+        .try_get_one::<String>(DERIVATION_PATH_ARG.name)?
+        .map(|path| parse_derivation_path(path))
+        .transpose()
+}
+
+/// Parses a derivation path given either as ACCOUNT/CHANGE or as a full path like
+/// m/44'/501'/0'/0'.
+pub(crate) fn parse_derivation_path(path: &str) -> Result<DerivationPath, Box<dyn error::Error>> {
+    if path.starts_with('m') {
+        DerivationPath::from_absolute_path_str(path)
+    } else {
+        DerivationPath::from_key_str(path)
+    }
+    .map_err(|err| err.into())
+}
+
+/// Checks that `seed_phrase` has a supported word count, consists of `language` words and
+/// has a valid BIP39 checksum, returning its word count. Unknown words are reported along
+/// with the closest word of the wordlist.
+pub(crate) fn validate_seed_phrase(seed_phrase: &str, language: Language) -> Result<usize, String> {
+    let words = seed_phrase.split_whitespace().collect::<Vec<_>>();
+    if !POSSIBLE_WORD_COUNTS.contains(&words.len().to_string().as_str()) {
+        return Err(format!(
+            "Seed phrase has {} words, expected one of {}",
+            words.len(),
+            POSSIBLE_WORD_COUNTS.join(", ")
+        ));
+    }
+    let unknown_words = words
+        .iter()
+        .enumerate()
+        .filter(|(_, word)| !in_wordlist(word, language))
+        .map(|(index, word)| {
+            format!(
+                "word {} '{word}' is not in the wordlist, did you mean '{}'?",
+                index + 1,
+                closest_word(word, language)
+            )
+        })
+        .collect::<Vec<_>>();
+    if !unknown_words.is_empty() {
+        return Err(format!("Invalid seed phrase: {}", unknown_words.join("; ")));
+    }
+    Mnemonic::validate(&nfkd(seed_phrase), language)
+        .map_err(|err| format!("Invalid seed phrase: {err}"))?;
+    Ok(words.len())
+}
+
+/// Normalizes `text` to NFKD, the form of the BIP39 wordlists. Unlike `Mnemonic::from_phrase`,
+/// `Mnemonic::validate` and the wordlist lookups do not do it themselves, so accented words
+/// typed in their composed form would not be found otherwise.
+fn nfkd(text: &str) -> Zeroizing<String> {
+    Zeroizing::new(text.nfkd().collect())
+}
+
+fn in_wordlist(word: &str, language: Language) -> bool {
+    language.wordmap().get_bits(&nfkd(word)).is_some()
+}
+
+fn closest_word(word: &str, language: Language) -> &'static str {
+    let word = nfkd(word);
+    language
+        .wordlist()
+        .get_words_by_prefix("")
+        .iter()
+        .min_by_key(|candidate| edit_distance(&word, candidate))
+        .unwrap()
+}
+
+/// Levenshtein distance between `a` and `b`, counted in chars.
+fn edit_distance(a: &str, b: &str) -> usize {
+    let b = b.chars().collect::<Vec<_>>();
+    let mut previous = (0..=b.len()).collect::<Vec<_>>();
+    for (i, a_char) in a.chars().enumerate() {
+        let mut current = vec![i + 1; b.len() + 1];
+        for (j, b_char) in b.iter().enumerate() {
+            let substitution = previous[j] + usize::from(a_char != *b_char);
+            current[j + 1] = substitution.min(previous[j + 1] + 1).min(current[j] + 1);
+        }
+        previous = current;
+    }
+    previous[b.len()]
+}
+
+/// Reads a seed phrase and its BIP39 passphrase, validates the phrase against the `language`
+/// wordlist, or the detected one when `language` is `None`, and derives the keypair, along
+/// `derivation_path` when one is given.
+pub(crate) fn keypair_from_seed_phrase(
+    matches: &ArgMatches,
+    language: Option<Language>,
+    derivation_path: Option<DerivationPath>,
+) -> Result<Keypair, Box<dyn error::Error>> {
+    let seed = seed_from_seed_phrase(matches, language)?;
+    keypair_from_bip39_seed(&seed, derivation_path)
+}
+
+/// Reads a seed phrase and its BIP39 passphrase and returns the BIP39 seed they make, so that
+/// several keypairs can be derived without prompting again.
+pub(crate) fn seed_from_seed_phrase(
+    matches: &ArgMatches,
+    language: Option<Language>,
+) -> Result<Seed, Box<dyn error::Error>> {
+    let mnemonic = prompt_mnemonic(language)?;
+    let passphrase = acquire_recovery_passphrase(matches)?;
+    Ok(Seed::new(&mnemonic, &passphrase))
+}
+
+/// Reads a seed phrase and validates it against the `language` wordlist, or the detected one
+/// when `language` is `None`.
+pub(crate) fn prompt_mnemonic(
+    language: Option<Language>,
+) -> Result<Mnemonic, Box<dyn error::Error>> {
+    let seed_phrase = prompt_seed_phrase("Seed phrase: ")?;
+    let language = match language {
+        Some(language) => language,
+        None => {
+            let name = detect_language(&seed_phrase)?;
+            // On stderr, since recover may write the keypair itself to stdout.
+            eprintln!("Detected seed phrase language: {name}");
+            language_from_name(name)
+        }
+    };
+    Ok(Mnemonic::from_phrase(&seed_phrase, language)
+        .map_err(|err| format!("Invalid seed phrase: {err}"))?)
+}
+
+/// Derives the keypair of a BIP39 `seed`, along `derivation_path` when one is given.
+pub(crate) fn keypair_from_bip39_seed(
+    seed: &Seed,
+    derivation_path: Option<DerivationPath>,
+) -> Result<Keypair, Box<dyn error::Error>> {
+    match derivation_path {
+        Some(derivation_path) => {
+            keypair_from_seed_and_derivation_path(seed.as_bytes(), Some(derivation_path))
+        }
+        None => keypair_from_seed(seed.as_bytes()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use solana_signer::Signer;
+    use std::collections::HashSet;
+    use std::io::Write;
+    use tempfile::NamedTempFile;
+
+    fn entropy_file(contents: &[u8]) -> NamedTempFile {
+        let mut file = NamedTempFile::new().unwrap();
+        file.write_all(contents).unwrap();
+        file
+    }
+
+    #[test]
+    fn test_detect_language() {
+        for (name, language) in [
+            ("english", Language::English),
+            ("japanese", Language::Japanese),
+            ("spanish", Language::Spanish),
+        ] {
+            let mnemonic = Mnemonic::from_entropy(&[7; 16], language).unwrap();
+            assert_eq!(detect_language(mnemonic.phrase()), Ok(name));
+        }
+
+        let mnemonic = Mnemonic::from_entropy(&[7; 16], Language::English).unwrap();
+        let typo = mnemonic.phrase().replacen("a", "q", 1);
+        let err = detect_language(&typo).unwrap_err();
+        assert!(
+            err.ends_with("(checked against english, the closest supported language)"),
+            "{err}"
+        );
+    }
+
+    #[test]
+    fn test_entropy_file_is_deterministic() {
+        let file = entropy_file(b"3 1 4 1 5 9 2 6 5 3 5 8 9 7 9 3 2 3 8 4 6 2 6 4");
+        let path = file.path().to_str().unwrap();
+        let pubkey = || {
+            let mnemonic =
+                mnemonic_from_entropy_file(path, MnemonicType::Words12, Language::English).unwrap();
+            let seed = Seed::new(&mnemonic, NO_PASSPHRASE);
+            keypair_from_seed(seed.as_bytes()).unwrap().pubkey()
+        };
+        assert_eq!(pubkey(), pubkey());
+
+        let other = entropy_file(b"2 7 1 8 2 8 1 8 2 8 4 5 9 0 4 5 2 3 5 3 6 0 2 8");
+        let other_phrase = mnemonic_from_entropy_file(
+            other.path().to_str().unwrap(),
+            MnemonicType::Words12,
+            Language::English,
+        )
+        .unwrap();
+        let phrase =
+            mnemonic_from_entropy_file(path, MnemonicType::Words12, Language::English).unwrap();
+        assert_ne!(phrase.phrase(), other_phrase.phrase());
+    }
+
+    #[test]
+    fn test_entropy_file_too_short() {
+        let file = entropy_file(&[7; MIN_ENTROPY_FILE_LEN - 1]);
+        let err = mnemonic_from_entropy_file(
+            file.path().to_str().unwrap(),
+            MnemonicType::Words24,
+            Language::English,
+        )
+        .unwrap_err();
+        assert!(
+            err.to_string()
+                .contains("holds 31 bytes, at least 32 are required")
+        );
+    }
+
+    #[test]
+    fn test_language_code() {
+        let codes = POSSIBLE_LANGUAGES
+            .iter()
+            .map(|name| language_code(language_from_name(name)))
+            .collect::<HashSet<_>>();
+        assert_eq!(codes.len(), POSSIBLE_LANGUAGES.len());
+        assert_eq!(language_code(Language::English), "en");
+        assert_eq!(language_code(Language::ChineseTraditional), "zh-Hant");
+    }
+
+    #[test]
+    fn test_validate_seed_phrase() {
+        let abandon = "abandon ".repeat(11);
+        assert_eq!(
+            validate_seed_phrase(&format!("{abandon}about"), Language::English),
+            Ok(12)
+        );
+        assert_eq!(
+            validate_seed_phrase(&format!("{abandon}abuot"), Language::English),
+            Err(
+                "Invalid seed phrase: word 12 'abuot' is not in the wordlist, did you mean \
+                 'about'?"
+                    .to_string()
+            )
+        );
+        assert_eq!(
+            validate_seed_phrase("abandon about", Language::English),
+            Err("Seed phrase has 2 words, expected one of 12, 24".to_string())
+        );
+        assert!(
+            validate_seed_phrase(&format!("{abandon}abandon"), Language::English)
+                .unwrap_err()
+                .starts_with("Invalid seed phrase: ")
+        );
+    }
+
+    #[test]
+    fn test_closest_word() {
+        assert_eq!(closest_word("abandn", Language::English), "abandon");
+        assert_eq!(closest_word("zooo", Language::English), "zoo");
+        assert_eq!(edit_distance("kitten", "sitting"), 3);
+        assert_eq!(edit_distance("", "abc"), 3);
+        assert_eq!(edit_distance("ábc", "abc"), 1);
+    }
+
+    #[test]
+    fn test_mnemonic_from_entropy_hex() {
+        // Test vectors of BIP39.
+        for (hex, phrase) in [
+            (
+                "00".repeat(16),
+                "abandon abandon abandon abandon abandon abandon abandon abandon abandon \
+                 abandon abandon about",
+            ),
+            (
+                "7f".repeat(16),
+                "legal winner thank year wave sausage worth useful legal winner thank yellow",
+            ),
+        ] {
+            let mnemonic =
+                mnemonic_from_entropy_hex(&hex, MnemonicType::Words12, Language::English).unwrap();
+            assert_eq!(mnemonic.phrase(), phrase);
+        }
+        assert_eq!(
+            mnemonic_from_entropy_hex(&"00".repeat(15), MnemonicType::Words12, Language::English)
+                .unwrap_err()
+                .to_string(),
+            "A 12 word seed phrase takes 16 bytes of entropy, found 15"
+        );
+    }
+
+    #[test]
+    fn test_keypair_from_bip39_seed() {
+        let mnemonic = Mnemonic::from_entropy(&[0; 16], Language::English).unwrap();
+        let seed = Seed::new(&mnemonic, "");
+        assert_eq!(
+            keypair_from_bip39_seed(&seed, None).unwrap().to_bytes(),
+            keypair_from_seed(seed.as_bytes()).unwrap().to_bytes()
+        );
+        let accounts = (0..3)
+            .map(|account| {
+                let derivation_path = DerivationPath::new_bip44(Some(account), Some(0));
+                keypair_from_bip39_seed(&seed, Some(derivation_path))
+                    .unwrap()
+                    .pubkey()
+            })
+            .collect::<HashSet<_>>();
+        assert_eq!(accounts.len(), 3);
+        assert!(
+            accounts.contains(
+                &keypair_from_bip39_seed(&seed, Some(DerivationPath::new_bip44(Some(0), Some(0))))
+                    .unwrap()
+                    .pubkey()
+            )
+        );
+    }
+
+    #[test]
+    fn test_parse_derivation_path() {
+        assert_eq!(
+            parse_derivation_path("1/0").unwrap(),
+            DerivationPath::new_bip44(Some(1), Some(0))
+        );
+        assert_eq!(
+            parse_derivation_path("m/44'/501'/1'/0'").unwrap(),
+            DerivationPath::new_bip44(Some(1), Some(0))
+        );
+        assert!(parse_derivation_path("m/bogus").is_err());
+        assert!(parse_derivation_path("one/0").is_err());
+    }
+
+    #[test]
+    fn test_recovery_checklist() {
+        assert!(RECOVERY_CHECKLIST.contains("solarium-keygen recover"));
+        assert!(RECOVERY_CHECKLIST.contains("solarium-keygen verify <PUBKEY> <FILEPATH>"));
+    }
+
+    #[test]
+    fn test_acquire_passphrase_and_message() {
+        let new_matches = |args: &[&str]| {
+            let matches = crate::app(Some("id.json".to_string()))
+                .try_get_matches_from([&["solarium-keygen", "new"], args].concat())
+                .unwrap();
+            matches.subcommand_matches("new").unwrap().clone()
+        };
+
+        for interactive in [false, true] {
+            let (passphrase, message) = acquire_passphrase_and_message(
+                &new_matches(&["--no-bip39-passphrase"]),
+                interactive,
+            )
+            .unwrap();
+            assert_eq!(passphrase.as_str(), NO_PASSPHRASE);
+            assert_eq!(message, "");
+        }
+
+        let err = acquire_passphrase_and_message(&new_matches(&["--silent"]), false)
+            .unwrap_err()
+            .to_string();
+        assert!(err.contains("--no-bip39-passphrase"), "{err}");
+    }
 }
@@ -0,0 +1,157 @@
+use solana_derivation_path::DerivationPath;
+use solana_pubkey::Pubkey;
+use std::fmt;
+
+// Scheme of hardware wallet signer URIs, as in `usb://ledger?key=0/0`
+pub(crate) const USB_SCHEME: &str = "usb";
+
+/// A hardware wallet signer location of the form
+/// `usb://<MANUFACTURER>[/<PUBKEY>][?key=<ACCOUNT>[/<CHANGE>] | ?full-path=<PATH>]`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) struct SignerUri {
+    pub(crate) scheme: String,
+    pub(crate) manufacturer: String,
+    pub(crate) pubkey: Option<Pubkey>,
+    pub(crate) derivation_path: Option<DerivationPath>,
+}
+
+impl fmt::Display for SignerUri {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} wallet", self.manufacturer)?;
+        if let Some(pubkey) = &self.pubkey {
+            write!(f, " {pubkey}")?;
+        }
+        if let Some(derivation_path) = &self.derivation_path {
+            write!(f, " at derivation path {derivation_path:?}")?;
+        }
+        write!(f, " ({}://)", self.scheme)
+    }
+}
+
+/// Returns whether `source` names a signer by URI rather than by file path.
+pub(crate) fn is_signer_uri(source: &str) -> bool {
+    source
+        .split_once("://")
+        .is_some_and(|(scheme, _)| scheme.eq_ignore_ascii_case(USB_SCHEME))
+}
+
+/// Parses a `usb://` signer URI into its manufacturer, optional wallet pubkey and optional
+/// derivation path.
+pub(crate) fn parse_signer_uri(uri: &str) -> Result<SignerUri, String> {
+    let (scheme, rest) = uri
+        .split_once("://")
+        .ok_or_else(|| format!("'{uri}' is not a signer URI"))?;
+    if !scheme.eq_ignore_ascii_case(USB_SCHEME) {
+        return Err(format!(
+            "unsupported signer URI scheme '{scheme}' in '{uri}'"
+        ));
+    }
+    let (location, query) = rest.split_once('?').unwrap_or((rest, ""));
+    let (manufacturer, pubkey) = location.split_once('/').unwrap_or((location, ""));
+    if manufacturer.is_empty() {
+        return Err(format!("signer URI '{uri}' does not name a manufacturer"));
+    }
+    let pubkey = match pubkey.trim_end_matches('/') {
+        "" => None,
+        pubkey => Some(
+            pubkey
+                .parse::<Pubkey>()
+                .map_err(|err| format!("invalid wallet pubkey in signer URI '{uri}': {err}"))?,
+        ),
+    };
+    let derivation_path = match query {
+        "" => None,
+        query => Some(
+            parse_derivation_path_query(query)
+                .map_err(|err| format!("invalid derivation path in signer URI '{uri}': {err}"))?,
+        ),
+    };
+    Ok(SignerUri {
+        scheme: scheme.to_ascii_lowercase(),
+        manufacturer: manufacturer.to_ascii_lowercase(),
+        pubkey,
+        derivation_path,
+    })
+}
+
+fn parse_derivation_path_query(query: &str) -> Result<DerivationPath, String> {
+    let (key, value) = query
+        .split_once('=')
+        .ok_or_else(|| format!("query '{query}' is not of the form key=value"))?;
+    let derivation_path = match key {
+        "key" => DerivationPath::from_key_str(value),
+        "full-path" => DerivationPath::from_absolute_path_str(value),
+        _ => {
+            return Err(format!(
+                "query key '{key}' is not supported, use key or full-path"
+            ));
+        }
+    };
+    derivation_path.map_err(|err| err.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_signer_uri() {
+        assert_eq!(
+            parse_signer_uri("usb://ledger"),
+            Ok(SignerUri {
+                scheme: "usb".to_string(),
+                manufacturer: "ledger".to_string(),
+                pubkey: None,
+                derivation_path: None,
+            })
+        );
+
+        let pubkey = Pubkey::new_unique();
+        let uri = parse_signer_uri(&format!("usb://ledger/{pubkey}")).unwrap();
+        assert_eq!(uri.manufacturer, "ledger");
+        assert_eq!(uri.pubkey, Some(pubkey));
+        assert_eq!(uri.derivation_path, None);
+
+        let uri = parse_signer_uri("USB://Ledger?key=0/0").unwrap();
+        assert_eq!(uri.scheme, "usb");
+        assert_eq!(uri.manufacturer, "ledger");
+        assert_eq!(uri.pubkey, None);
+        assert_eq!(
+            uri.derivation_path,
+            Some(DerivationPath::new_bip44(Some(0), Some(0)))
+        );
+
+        let uri = parse_signer_uri(&format!("usb://ledger/{pubkey}?key=1")).unwrap();
+        assert_eq!(uri.pubkey, Some(pubkey));
+        assert_eq!(
+            uri.derivation_path,
+            Some(DerivationPath::new_bip44(Some(1), None))
+        );
+
+        let uri = parse_signer_uri("usb://ledger?full-path=m/44/501/2/3").unwrap();
+        assert_eq!(
+            uri.derivation_path,
+            Some(DerivationPath::new_bip44(Some(2), Some(3)))
+        );
+    }
+
+    #[test]
+    fn test_parse_signer_uri_malformed() {
+        assert!(parse_signer_uri("ledger").is_err());
+        assert!(parse_signer_uri("http://ledger").is_err());
+        assert!(parse_signer_uri("usb://").is_err());
+        assert!(parse_signer_uri("usb:///11111111111111111111111111111111").is_err());
+        assert!(parse_signer_uri("usb://ledger/not-a-pubkey").is_err());
+        assert!(parse_signer_uri("usb://ledger?key").is_err());
+        assert!(parse_signer_uri("usb://ledger?account=0").is_err());
+        assert!(parse_signer_uri("usb://ledger?key=x/0").is_err());
+    }
+
+    #[test]
+    fn test_is_signer_uri() {
+        assert!(is_signer_uri("usb://ledger"));
+        assert!(is_signer_uri("USB://ledger"));
+        assert!(!is_signer_uri("id.json"));
+        assert!(!is_signer_uri("file://id.json"));
+    }
+}
@@ -0,0 +1,182 @@
+use solana_keypair::{Keypair, read_keypair_file, write_keypair_file};
+use solana_pubkey::Pubkey;
+use solana_signature::Signature;
+use solana_signer::Signer;
+use std::fs;
+use std::path::Path;
+use std::process::Command;
+use std::str::FromStr;
+
+// Prefix keygen signs messages behind, so that they can never be replayed as transactions.
+const SIGNED_MESSAGE_DOMAIN: &[u8] = b"\xffsolarium signed message:\n";
+
+/// A keygen command run with `home` as its home directory, so that no config file or default
+/// keypair of the user running the tests is picked up.
+fn keygen_command(home: &Path) -> Command {
+    let mut command = Command::new(env!("CARGO_BIN_EXE_solarium-keygen"));
+    command.env("HOME", home).env_remove("USERPROFILE");
+    command
+}
+
+/// Runs `command`, failing unless it succeeds, and returns its stdout.
+fn run(command: &mut Command) -> String {
+    let output = command.output().unwrap();
+    assert!(
+        output.status.success(),
+        "{}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+    String::from_utf8(output.stdout).unwrap()
+}
+
+#[test]
+fn test_config_keypair_path_is_default_outfile() {
+    let dir = tempfile::tempdir().unwrap();
+    let keypair_path = dir.path().join("custom.json");
+    let keypair_path = keypair_path.to_str().unwrap();
+    let config_file = dir.path().join("config.yml");
+    fs::write(
+        &config_file,
+        format!(
+            "json_rpc_url: http://localhost:8899\nwebsocket_url: ''\nkeypair_path: {keypair_path}\n"
+        ),
+    )
+    .unwrap();
+    let keygen = || {
+        let mut command = keygen_command(dir.path());
+        command.arg("-C").arg(&config_file);
+        command
+    };
+
+    // The help may wrap its lines, so compare it with whitespace collapsed.
+    let help = run(keygen().args(["new", "--help"]));
+    let help = help.split_whitespace().collect::<Vec<_>>().join(" ");
+    assert!(
+        help.contains(&format!("[default: {keypair_path}]")),
+        "{help}"
+    );
+
+    let stdout = run(keygen().args(["new", "--no-bip39-passphrase", "--silent"]));
+    assert_eq!(stdout, format!("Wrote new keypair to {keypair_path}\n"));
+    read_keypair_file(keypair_path).unwrap();
+}
+
+#[test]
+fn test_new_outfile_stdout() {
+    let home = tempfile::tempdir().unwrap();
+    let output = keygen_command(home.path())
+        .args(["new", "--outfile", "-", "--no-bip39-passphrase"])
+        .output()
+        .unwrap();
+    let stderr = String::from_utf8(output.stderr).unwrap();
+    assert!(output.status.success(), "{stderr}");
+
+    // stdout holds the keypair alone, the seed phrase and pubkey went to stderr.
+    let bytes: Vec<u8> = serde_json::from_slice(&output.stdout).unwrap();
+    assert_eq!(bytes.len(), 64);
+    let keypair = Keypair::try_from(bytes.as_slice()).unwrap();
+    assert!(
+        stderr.contains(&format!("pubkey: {}\n", keypair.pubkey())),
+        "{stderr}"
+    );
+}
+
+#[test]
+fn test_convert_round_trip() {
+    let dir = tempfile::tempdir().unwrap();
+    let path = |name: &str| dir.path().join(name).to_str().unwrap().to_string();
+    let keypair = Keypair::new();
+    write_keypair_file(&keypair, path("id.json")).unwrap();
+
+    run(keygen_command(dir.path()).args([
+        "convert",
+        "--from",
+        &path("id.json"),
+        "--to",
+        &path("id.b58"),
+        "--output-format",
+        "base58",
+    ]));
+    assert_eq!(
+        fs::read_to_string(path("id.b58")).unwrap().trim(),
+        keypair.to_base58_string()
+    );
+
+    run(keygen_command(dir.path()).args([
+        "convert",
+        "--from",
+        &path("id.b58"),
+        "--to",
+        &path("round-trip.json"),
+    ]));
+    let converted = read_keypair_file(path("round-trip.json")).unwrap();
+    assert_eq!(converted.to_bytes(), keypair.to_bytes());
+    assert_eq!(converted.pubkey(), keypair.pubkey());
+    assert_eq!(
+        fs::read_to_string(path("round-trip.json")).unwrap(),
+        fs::read_to_string(path("id.json")).unwrap()
+    );
+}
+
+#[test]
+fn test_new_output_json() {
+    let dir = tempfile::tempdir().unwrap();
+    let new_json = |outfile: &str, args: &[&str]| {
+        let outfile = dir.path().join(outfile).to_str().unwrap().to_string();
+        let stdout = run(keygen_command(dir.path())
+            .args(["new", "--output", "json", "--no-bip39-passphrase"])
+            .args(["--outfile", &outfile])
+            .args(args));
+        let output: serde_json::Value = serde_json::from_str(&stdout).unwrap();
+        let keypair = read_keypair_file(&outfile).unwrap();
+        assert_eq!(output["pubkey"], keypair.pubkey().to_string());
+        assert_eq!(output["path"], outfile);
+        output
+    };
+
+    let output = new_json("id.json", &[]);
+    assert_eq!(
+        output["seedPhrase"]
+            .as_str()
+            .unwrap()
+            .split_whitespace()
+            .count(),
+        12
+    );
+
+    let output = new_json("silent.json", &["--silent"]);
+    assert!(output.get("seedPhrase").is_none(), "{output}");
+}
+
+#[test]
+fn test_new_attest() {
+    let dir = tempfile::tempdir().unwrap();
+    let outfile = dir.path().join("id.json");
+    let message = "I control this key";
+    let stdout = run(keygen_command(dir.path())
+        .args([
+            "new",
+            "--no-bip39-passphrase",
+            "--silent",
+            "--attest",
+            message,
+        ])
+        .arg("--outfile")
+        .arg(&outfile));
+
+    let field = |name: &str| {
+        stdout
+            .lines()
+            .find_map(|line| line.strip_prefix(&format!("  {name}: ")))
+            .unwrap_or_else(|| panic!("no {name} in {stdout}"))
+    };
+    let pubkey = Pubkey::from_str(field("pubkey")).unwrap();
+    let signature = Signature::from_str(field("signature")).unwrap();
+    assert_eq!(pubkey, read_keypair_file(&outfile).unwrap().pubkey());
+    assert_eq!(field("message"), message);
+    assert!(signature.verify(
+        pubkey.as_ref(),
+        &[SIGNED_MESSAGE_DOMAIN, message.as_bytes()].concat()
+    ));
+    assert!(!signature.verify(pubkey.as_ref(), message.as_bytes()));
+}